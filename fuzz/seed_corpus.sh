@@ -0,0 +1,20 @@
+#!/bin/sh
+# Starts each fuzz target's corpus from the day's example inputs, then fuzz a day with:
+#
+#     cargo +nightly fuzz run day17 -- -timeout=5
+#
+# A run fails if the day panics, overflows or takes longer than the timeout on any input, whether
+# parsing it or solving either part.
+set -e
+cd "$(dirname "$0")"
+for input in ../inputs/[0-9][0-9]e*.txt ../inputs/[0-9][0-9]e/*.txt; do
+    # A pattern that matches nothing is left as it is.
+    [ -e "$input" ] || continue
+    case "$input" in
+    */[0-9][0-9]e/*) name="$(basename "$(dirname "$input")")-$(basename "$input" .txt)" ;;
+    *) name=$(basename "$input" .txt) ;;
+    esac
+    day=$(echo "$name" | cut -c1-2)
+    mkdir -p "corpus/day$day"
+    cp "$input" "corpus/day$day/$name"
+done
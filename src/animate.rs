@@ -0,0 +1,354 @@
+//! Plays a day's frames in the terminal with ANSI escape codes, redrawing the picture in place.
+//! Two pixels share each character cell: the top one as the foreground colour of `▀` and the
+//! bottom one as its background.
+//!
+//! The animation is controlled from stdin: space pauses and resumes, `s` or `.` shows the next
+//! frame while paused, `+` and `-` change the speed and `q` stops. When stdin is a terminal it's
+//! switched to unbuffered input with `stty` so keys work without pressing enter, otherwise each
+//! line is read as a sequence of keys. Ctrl-C comes in as a key too, and stops the animation like
+//! `q` does, so the terminal is always switched back.
+
+use std::{
+    fmt::Write as _,
+    io::{IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use crate::render::{FrameSink, Image, Rgb};
+
+/// How often the screen is redrawn at most. Faster speeds skip frames instead.
+const MAX_REDRAWS_PER_SECOND: u32 = 60;
+
+pub const MIN_FPS: u32 = 1;
+pub const MAX_FPS: u32 = 3840;
+
+/// Returned by [`Animation::frame`] when the viewer quits, to stop the day from drawing any more
+/// frames. It isn't a failure, so callers should check for it with `error.is::<Quit>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quit;
+
+impl std::fmt::Display for Quit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Animation stopped")
+    }
+}
+
+impl std::error::Error for Quit {}
+
+/// The state of the playback controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Controls {
+    pub fps: u32,
+    pub paused: bool,
+    /// Frames to show before pausing again, after stepping while paused.
+    pub steps: u32,
+    pub quit: bool,
+}
+
+impl Controls {
+    pub fn new(fps: u32) -> Controls {
+        Controls {
+            fps: fps.clamp(MIN_FPS, MAX_FPS),
+            paused: false,
+            steps: 0,
+            quit: false,
+        }
+    }
+
+    /// Applies a key press. Unknown keys are ignored.
+    pub fn apply(&mut self, key: u8) {
+        match key {
+            b' ' | b'p' => self.paused = !self.paused,
+            b's' | b'.' if self.paused => self.steps += 1,
+            b'+' | b'=' => self.fps = (self.fps * 2).min(MAX_FPS),
+            b'-' | b'_' => self.fps = (self.fps / 2).max(MIN_FPS),
+            // Escape and Ctrl-C.
+            b'q' | 0x1b | 0x03 => self.quit = true,
+            _ => {}
+        }
+    }
+
+    /// Whether the next frame can be shown, or the animation has to wait for a key.
+    fn can_advance(&self) -> bool {
+        !self.paused || self.steps > 0
+    }
+
+    /// How many frames go by between redraws, to go faster than the screen is redrawn.
+    fn frames_per_redraw(&self) -> usize {
+        if self.paused {
+            1
+        } else {
+            self.fps.div_ceil(MAX_REDRAWS_PER_SECOND) as usize
+        }
+    }
+
+    fn delay(&self) -> Duration {
+        let redraws = self.fps.min(MAX_REDRAWS_PER_SECOND);
+        Duration::from_secs(1) / redraws
+    }
+}
+
+/// A [`FrameSink`] that draws each frame in the terminal.
+pub struct Animation<W: Write> {
+    out: W,
+    controls: Controls,
+    keys: Receiver<u8>,
+    frame: usize,
+    terminal: Option<TerminalMode>,
+}
+
+impl Animation<std::io::Stdout> {
+    /// Plays on stdout, with the controls read from stdin.
+    pub fn stdout(fps: u32) -> anyhow::Result<Animation<std::io::Stdout>> {
+        let terminal = TerminalMode::unbuffered();
+        let (sender, keys) = mpsc::channel();
+        std::thread::spawn(move || {
+            for key in std::io::stdin().lock().bytes() {
+                let Ok(key) = key else { break };
+                if sender.send(key).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut out = std::io::stdout();
+        // Clear the screen and hide the cursor until the animation is done.
+        write!(out, "\x1b[2J\x1b[?25l")?;
+        Ok(Animation {
+            out,
+            controls: Controls::new(fps),
+            keys,
+            frame: 0,
+            terminal,
+        })
+    }
+}
+
+impl<W: Write> Animation<W> {
+    pub fn new(out: W, keys: Receiver<u8>, fps: u32) -> Animation<W> {
+        Animation {
+            out,
+            controls: Controls::new(fps),
+            keys,
+            frame: 0,
+            terminal: None,
+        }
+    }
+
+    pub fn controls(&self) -> Controls {
+        self.controls
+    }
+
+    fn apply_pending_keys(&mut self) {
+        while let Ok(key) = self.keys.try_recv() {
+            self.controls.apply(key);
+        }
+    }
+
+    fn redraw(&mut self, image: &Image) -> anyhow::Result<()> {
+        let mut screen = String::from("\x1b[H");
+        draw(image, &mut screen);
+        let state = if self.controls.paused {
+            "paused"
+        } else {
+            "playing"
+        };
+        write!(
+            screen,
+            "\x1b[0m\x1b[Kframe {:<6} {:>4} fps  {state:<7}  [space] pause  [s] step  [+/-] speed  [q] quit\r\n",
+            self.frame, self.controls.fps
+        )?;
+        self.out.write_all(screen.as_bytes())?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Waits for the frame's turn to be over, handling keys as they come in.
+    fn wait(&mut self) -> anyhow::Result<()> {
+        let deadline = Instant::now() + self.controls.delay();
+        loop {
+            if self.controls.quit {
+                return Err(Quit.into());
+            }
+            let timeout = if self.controls.can_advance() {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(());
+                }
+                deadline - now
+            } else {
+                // Nothing to do until a key arrives, but keep checking now and then in case the
+                // keys stop coming.
+                Duration::from_millis(250)
+            };
+            match self.keys.recv_timeout(timeout) {
+                Ok(key) => self.controls.apply(key),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) if self.controls.can_advance() => {
+                    std::thread::sleep(timeout)
+                }
+                // Paused with no way to ever resume.
+                Err(RecvTimeoutError::Disconnected) => return Err(Quit.into()),
+            }
+        }
+    }
+}
+
+impl<W: Write> FrameSink for Animation<W> {
+    fn frame(&mut self, image: &Image) -> anyhow::Result<()> {
+        self.apply_pending_keys();
+        if self.controls.quit {
+            return Err(Quit.into());
+        }
+        let n = self.frame;
+        self.frame += 1;
+        if self.controls.steps == 0 && !n.is_multiple_of(self.controls.frames_per_redraw()) {
+            return Ok(());
+        }
+        self.redraw(image)?;
+        self.wait()?;
+        self.controls.steps = self.controls.steps.saturating_sub(1);
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for Animation<W> {
+    fn drop(&mut self) {
+        // Show the cursor again. The terminal mode is restored when `terminal` is dropped.
+        let _ = write!(self.out, "\x1b[0m\x1b[?25h");
+        let _ = self.out.flush();
+        self.terminal.take();
+    }
+}
+
+/// Draws an image with two pixels per character, ending each line with a colour reset.
+pub fn draw(image: &Image, screen: &mut String) {
+    let rows = image.rows().collect::<Vec<_>>();
+    for pair in rows.chunks(2) {
+        let top = pair[0];
+        let bottom = pair.get(1);
+        for (x, &Rgb(r, g, b)) in top.iter().enumerate() {
+            let _ = write!(screen, "\x1b[38;2;{r};{g};{b}m");
+            match bottom {
+                Some(bottom) => {
+                    let Rgb(r, g, b) = bottom[x];
+                    let _ = write!(screen, "\x1b[48;2;{r};{g};{b}m");
+                }
+                None => screen.push_str("\x1b[49m"),
+            }
+            screen.push('▀');
+        }
+        screen.push_str("\x1b[0m\r\n");
+    }
+}
+
+/// The terminal's settings before it was switched to unbuffered input, restored when dropped.
+struct TerminalMode {
+    saved: String,
+}
+
+impl TerminalMode {
+    /// Switches a terminal on stdin to reading keys one at a time without echoing them, or does
+    /// nothing when stdin isn't a terminal or `stty` isn't available. Ctrl-C is read as a key
+    /// instead of killing the process, which would leave the terminal in this mode.
+    fn unbuffered() -> Option<TerminalMode> {
+        if !std::io::stdin().is_terminal() {
+            return None;
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Some(TerminalMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for TerminalMode {
+    fn drop(&mut self) {
+        stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::utils::Vec2;
+
+    #[test]
+    fn test_controls() {
+        let mut controls = Controls::new(30);
+        controls.apply(b'+');
+        assert_eq!(controls.fps, 60);
+        controls.apply(b'-');
+        controls.apply(b'-');
+        assert_eq!(controls.fps, 15);
+
+        controls.apply(b's');
+        assert_eq!(controls.steps, 0, "stepping only works while paused");
+        controls.apply(b' ');
+        assert!(!controls.can_advance());
+        controls.apply(b's');
+        assert!(controls.can_advance());
+
+        for _ in 0..20 {
+            controls.apply(b'+');
+        }
+        assert_eq!(controls.fps, MAX_FPS);
+        controls.apply(b'q');
+        assert!(controls.quit);
+
+        // Ctrl-C, which arrives as a key while the terminal is unbuffered.
+        let mut controls = Controls::new(30);
+        controls.apply(0x03);
+        assert!(controls.quit);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut image = Image::filled(2, 3, Rgb::BLACK);
+        image.set(Vec2 { x: 1, y: 1 }, Rgb::WHITE);
+        let mut screen = String::new();
+        draw(&image, &mut screen);
+        let lines = screen.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "\x1b[38;2;0;0;0m\x1b[48;2;0;0;0m▀\x1b[38;2;0;0;0m\x1b[48;2;255;255;255m▀\x1b[0m"
+        );
+        assert!(lines[1].ends_with("\x1b[49m▀\x1b[0m"));
+    }
+
+    #[test]
+    fn test_animation_plays_and_quits() {
+        let (keys, receiver) = mpsc::channel();
+        let mut out = Vec::new();
+        {
+            let mut animation = Animation::new(&mut out, receiver, MAX_FPS);
+            let image = Image::filled(1, 1, Rgb::RED);
+            for _ in 0..3 {
+                animation.frame(&image).unwrap();
+            }
+            keys.send(b'q').unwrap();
+            let error = animation.frame(&image).unwrap_err();
+            assert!(error.is::<Quit>());
+        }
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\x1b[38;2;220;50;47m"));
+        assert!(out.ends_with("\x1b[?25h"));
+    }
+}
@@ -0,0 +1,307 @@
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::InputKind,
+    solution::{Params, Parsed, Puzzle},
+};
+
+pub const DEFAULT_BASELINE: &str = "target/bench-baseline.json";
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub iterations: usize,
+    pub warmup: usize,
+    /// How much slower (as a fraction) the median may get before it's flagged as a regression.
+    pub threshold: f64,
+    /// Overwrite the baseline with the results of this run.
+    pub save: bool,
+    pub baseline: String,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            iterations: 10,
+            warmup: 3,
+            threshold: 0.1,
+            save: false,
+            baseline: DEFAULT_BASELINE.to_string(),
+        }
+    }
+}
+
+/// Summary statistics over a set of samples, stored in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub p95: u64,
+    pub mean: u64,
+    pub stddev: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut nanos = samples
+            .iter()
+            .map(|d| d.as_nanos() as u64)
+            .collect::<Vec<_>>();
+        nanos.sort_unstable();
+
+        let n = nanos.len();
+        let median = if n % 2 == 0 {
+            (nanos[n / 2 - 1] + nanos[n / 2]) / 2
+        } else {
+            nanos[n / 2]
+        };
+        let p95 = nanos[(n * 95).div_ceil(100).max(1) - 1];
+        let mean = nanos.iter().sum::<u64>() as f64 / n as f64;
+        let variance = nanos
+            .iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Stats {
+            min: nanos[0],
+            median,
+            p95,
+            mean: mean as u64,
+            stddev: variance.sqrt() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub parse: Stats,
+    /// `None` if the part failed, as it stops being timed after its first error.
+    pub part1: Option<Stats>,
+    pub part2: Option<Stats>,
+    /// Why each failed part failed. Errors aren't saved in the baseline.
+    #[serde(skip)]
+    pub errors: BTreeMap<u8, String>,
+}
+
+/// Saved benchmark results, keyed by day (with the example's name as a suffix for examples).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline(BTreeMap<String, DayBench>);
+
+impl Baseline {
+    pub fn load(path: &str) -> anyhow::Result<Baseline> {
+        if !Path::new(path).exists() {
+            return Ok(Baseline::default());
+        }
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).with_context(|| format!("Invalid baseline {path}"))
+    }
+
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u16, kind: &InputKind) -> Option<&DayBench> {
+        self.0.get(&Baseline::key(day, kind))
+    }
+
+    pub fn insert(&mut self, day: u16, kind: &InputKind, bench: DayBench) {
+        self.0.insert(Baseline::key(day, kind), bench);
+    }
+
+    fn key(day: u16, kind: &InputKind) -> String {
+        format!("{day:02}{}", kind.suffix())
+    }
+}
+
+/// Runs the parser and every part of the day `options.warmup` times without measuring, then
+/// `options.iterations` times while timing each step. A part that fails is recorded with its error
+/// and not run again, so the other part still gets timed.
+pub fn bench(
+    puzzle: &dyn Puzzle,
+    data: &str,
+    params: &Params,
+    options: &BenchOptions,
+) -> anyhow::Result<DayBench> {
+    let mut errors = BTreeMap::new();
+    let mut solve = |parsed: &dyn Parsed, part: u8, samples: Option<&mut Vec<Duration>>| {
+        if errors.contains_key(&part) || part > puzzle.parts() {
+            return;
+        }
+        let start = std::time::Instant::now();
+        let answer = match part {
+            1 => parsed.part1(),
+            _ => parsed.part2(),
+        };
+        match std::hint::black_box(answer) {
+            Ok(_) => {
+                if let Some(samples) = samples {
+                    samples.push(start.elapsed());
+                }
+            }
+            Err(e) => {
+                errors.insert(part, e.to_string());
+            }
+        }
+    };
+
+    for _ in 0..options.warmup {
+        let parsed = puzzle.parse(data, params)?;
+        solve(&*parsed, 1, None);
+        solve(&*parsed, 2, None);
+    }
+
+    let mut parse = Vec::with_capacity(options.iterations);
+    let mut part1 = Vec::with_capacity(options.iterations);
+    let mut part2 = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = std::time::Instant::now();
+        let parsed = puzzle.parse(data, params)?;
+        parse.push(start.elapsed());
+
+        solve(&*parsed, 1, Some(&mut part1));
+        solve(&*parsed, 2, Some(&mut part2));
+    }
+
+    let stats = |part, samples: &[Duration]| {
+        (part <= puzzle.parts() && !errors.contains_key(&part))
+            .then(|| Stats::from_samples(samples))
+    };
+    let part1 = stats(1, &part1);
+    let part2 = stats(2, &part2);
+    Ok(DayBench {
+        parse: Stats::from_samples(&parse),
+        part1,
+        part2,
+        errors,
+    })
+}
+
+/// How much slower the new median is compared to the baseline, as a fraction.
+fn change(new: &Stats, old: &Stats) -> f64 {
+    (new.median as f64 - old.median as f64) / (old.median as f64).max(1.0)
+}
+
+fn nanos(n: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(n))
+}
+
+/// Prints the results for a day and returns whether any step regressed past the threshold.
+pub fn report(
+    day: u16,
+    bench: &DayBench,
+    baseline: Option<&DayBench>,
+    options: &BenchOptions,
+) -> bool {
+    println!(
+        "Day {day} ({} iterations, {} warm-up)",
+        options.iterations, options.warmup
+    );
+    println!(
+        "{:<6}  {:>10}  {:>10}  {:>10}  {:>10}  Baseline",
+        "Step", "Min", "Median", "p95", "Stddev"
+    );
+
+    let mut regressed = false;
+    let steps = [
+        ("parse", Some(bench.parse), baseline.map(|b| b.parse)),
+        ("part1", bench.part1, baseline.and_then(|b| b.part1)),
+        ("part2", bench.part2, baseline.and_then(|b| b.part2)),
+    ];
+    for (part, (name, stats, old)) in steps.into_iter().enumerate() {
+        let Some(stats) = stats else {
+            // A part without stats either failed or isn't one the day has.
+            if let Some(error) = bench.errors.get(&(part as u8)) {
+                println!("{name:<6}  error: {error}");
+            }
+            continue;
+        };
+        let comparison = match old {
+            Some(old) => {
+                let change = change(&stats, &old);
+                if change > options.threshold {
+                    regressed = true;
+                    format!("{:+.1}% REGRESSION", change * 100.0)
+                } else {
+                    format!("{:+.1}%", change * 100.0)
+                }
+            }
+            None => String::from("-"),
+        };
+        println!(
+            "{:<6}  {:>10}  {:>10}  {:>10}  {:>10}  {comparison}",
+            name,
+            nanos(stats.min),
+            nanos(stats.median),
+            nanos(stats.p95),
+            nanos(stats.stddev),
+        );
+    }
+    regressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 4, 2, 3].map(Duration::from_nanos);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, 1);
+        assert_eq!(stats.median, 3);
+        assert_eq!(stats.p95, 5);
+        assert_eq!(stats.mean, 3);
+        assert_eq!(stats.stddev, 1);
+
+        let samples = (1..=100).map(Duration::from_nanos).collect::<Vec<_>>();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.median, 50);
+        assert_eq!(stats.p95, 95);
+    }
+
+    #[test]
+    fn test_change() {
+        let stats = |median| Stats {
+            min: 0,
+            median,
+            p95: 0,
+            mean: 0,
+            stddev: 0,
+        };
+        assert_eq!(change(&stats(150), &stats(100)), 0.5);
+        assert_eq!(change(&stats(50), &stats(100)), -0.5);
+    }
+
+    #[test]
+    fn test_failing_part() {
+        let puzzle = crate::find(14).unwrap();
+        let options = BenchOptions {
+            iterations: 2,
+            warmup: 1,
+            ..BenchOptions::default()
+        };
+        let bench = bench(puzzle, "p=0,0 v=1,1\n", &Params::new(), &options).unwrap();
+        assert!(bench.part1.is_some());
+        assert_eq!(bench.part2, None);
+        assert_eq!(bench.errors[&2], "No possible tree found");
+
+        let puzzle = crate::find(25).unwrap();
+        let lock = "#####\n.....\n.....\n.....\n.....\n.....\n.....\n";
+        let single = super::bench(puzzle, lock, &Params::new(), &options).unwrap();
+        assert!(single.part1.is_some());
+        assert_eq!(single.part2, None);
+        assert!(single.errors.is_empty());
+
+        let saved = serde_json::to_string(&bench).unwrap();
+        let loaded: DayBench = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.part1, bench.part1);
+        assert!(loaded.errors.is_empty());
+    }
+}
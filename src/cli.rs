@@ -0,0 +1,349 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use aoc2024::{
+    animate::{MAX_FPS, MIN_FPS},
+    bench::{BenchOptions, DEFAULT_BASELINE},
+    input::{FsProvider, HttpProvider, InputKind, InputProvider, DEFAULT_BASE_URL},
+    params::{self, Params},
+    render::{FrameWriter, ImageFormat},
+};
+
+#[derive(Debug, Parser)]
+#[command(about = "Advent of Code 2024 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+    /// Directory to look for inputs in, after `AOC_INPUT_DIR`
+    #[arg(long, global = true, value_name = "DIR")]
+    pub input_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Solve a single day
+    Run {
+        day: u16,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        params: ParamArgs,
+        /// Only solve one part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        #[command(flatten)]
+        animate: AnimateArgs,
+    },
+    /// Solve several days and print how long each step took
+    All {
+        /// `all`, a single day, or a range such as `1..=12`
+        #[arg(default_value = "all")]
+        days: String,
+        /// Use an example input: `e` (`NNe.txt`) unless another one is named with `--example=NAME`
+        #[arg(long, value_name = "NAME", num_args = 0..=1, require_equals = true, default_missing_value = InputKind::DEFAULT_EXAMPLE)]
+        example: Option<String>,
+        #[command(flatten)]
+        download: DownloadArgs,
+        #[command(flatten)]
+        params: ParamArgs,
+        /// Only solve one part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Check every day against the answers in `answers/`
+    Verify {
+        /// `all`, a single day, or a range such as `1..=12`
+        #[arg(default_value = "all")]
+        days: String,
+    },
+    /// Benchmark days, optionally comparing to a saved baseline
+    Bench {
+        /// `all`, a single day, or a range such as `1..=12`
+        days: String,
+        /// Use an example input: `e` (`NNe.txt`) unless another one is named with `--example=NAME`
+        #[arg(long, value_name = "NAME", num_args = 0..=1, require_equals = true, default_missing_value = InputKind::DEFAULT_EXAMPLE)]
+        example: Option<String>,
+        #[command(flatten)]
+        download: DownloadArgs,
+        #[command(flatten)]
+        params: ParamArgs,
+        #[command(flatten)]
+        options: BenchArgs,
+    },
+    /// Draw a day as images, one per frame for days that simulate something
+    Render {
+        day: u16,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        params: ParamArgs,
+        #[command(flatten)]
+        frames: FrameArgs,
+    },
+    /// Make up a random input for a day, to test or benchmark it at scale
+    Gen {
+        day: u16,
+        /// Seed for the random numbers, the same seed always gives the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// How many times bigger than a real input to make it. Days 9 and 23 are slow to solve, so
+        /// theirs grow slower, taking about this many times longer to solve instead
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..=1000))]
+        size: u64,
+        /// Write the input to a file instead of standard output
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Add a new day to the project from `new_day.tmpl`
+    New { day: u16 },
+}
+
+#[derive(Debug, Args)]
+pub struct InputArgs {
+    /// Use an example input: `e` (`NNe.txt`) unless another one is named with `--example=NAME`
+    #[arg(
+        long,
+        value_name = "NAME",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = InputKind::DEFAULT_EXAMPLE,
+        conflicts_with_all = ["input", "stdin"]
+    )]
+    pub example: Option<String>,
+    /// Read the puzzle input from a file
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["stdin", "download"])]
+    pub input: Option<PathBuf>,
+    /// Read the puzzle input from standard input
+    #[arg(long, conflicts_with = "download")]
+    pub stdin: bool,
+    #[command(flatten)]
+    pub download: DownloadArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ParamArgs {
+    /// Override one of the day's parameters, such as `--param width=11`
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = params::parse_assignment)]
+    pub params: Vec<(String, String)>,
+}
+
+impl ParamArgs {
+    pub fn params(&self) -> Params {
+        self.params.iter().cloned().collect()
+    }
+}
+
+/// The input chosen by an `--example` flag.
+pub fn input_kind(example: Option<String>) -> InputKind {
+    match example {
+        Some(name) => InputKind::Example(name),
+        None => InputKind::Real,
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct DownloadArgs {
+    /// Download inputs that aren't in `inputs/` yet, and save them there
+    #[arg(long)]
+    pub download: bool,
+    /// Server to download inputs from
+    #[arg(long, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+    pub base_url: String,
+    /// Session cookie to download inputs with
+    #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+    pub session: Option<String>,
+}
+
+impl DownloadArgs {
+    pub fn provider(&self, input_dir: Option<&Path>) -> anyhow::Result<Box<dyn InputProvider>> {
+        let inputs = FsProvider::resolve(input_dir);
+        if !self.download {
+            return Ok(Box::new(inputs));
+        }
+        let Some(session) = self.session.clone() else {
+            anyhow::bail!("Downloading inputs needs a session token (--session or AOC_SESSION)");
+        };
+        Ok(Box::new(HttpProvider {
+            base_url: self.base_url.clone(),
+            session,
+            cache: inputs,
+        }))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Number of measured iterations
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub iterations: u64,
+    /// Number of iterations to run before measuring
+    #[arg(long, default_value_t = 3)]
+    pub warmup: u64,
+    /// Percentage the median may slow down before it's flagged as a regression
+    #[arg(long, default_value_t = 10.0)]
+    pub threshold: f64,
+    /// Where the baseline is stored
+    #[arg(long, default_value = DEFAULT_BASELINE)]
+    pub baseline: String,
+    /// Overwrite the baseline with this run's results
+    #[arg(long)]
+    pub save: bool,
+}
+
+impl From<BenchArgs> for BenchOptions {
+    fn from(args: BenchArgs) -> Self {
+        BenchOptions {
+            iterations: args.iterations as usize,
+            warmup: args.warmup as usize,
+            threshold: args.threshold / 100.0,
+            save: args.save,
+            baseline: args.baseline,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct FrameArgs {
+    /// Directory to write the frames to
+    #[arg(long, default_value = "frames")]
+    pub out: PathBuf,
+    /// Image format, `ppm` or `png` (which needs the `png` feature)
+    #[arg(long, default_value_t = ImageFormat::Ppm)]
+    pub image_format: ImageFormat,
+    /// Size of each cell in pixels
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=64))]
+    pub scale: u64,
+    /// Only write every nth frame
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    pub every: u64,
+}
+
+impl FrameArgs {
+    pub fn writer(&self) -> anyhow::Result<FrameWriter> {
+        Ok(FrameWriter::new(&self.out, self.image_format)?
+            .scale(self.scale as usize)
+            .every(self.every as usize))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AnimateArgs {
+    /// Watch the day's frames in the terminal before solving it. Space pauses, `s` steps while
+    /// paused, `+` and `-` change the speed and `q` stops
+    #[arg(long, conflicts_with = "stdin")]
+    pub animate: bool,
+    /// Frames per second to start the animation at
+    #[arg(long, default_value_t = 30, requires = "animate", value_parser = clap::value_parser!(u32).range(MIN_FPS as i64..=MAX_FPS as i64))]
+    pub fps: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    /// A JSON array of result records
+    Json,
+    /// One JSON result record per line
+    Jsonl,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_conflicting_inputs() {
+        let result = Cli::try_parse_from(["aoc2024", "run", "3", "--example", "--stdin"]);
+        assert!(result.is_err());
+
+        let result = Cli::try_parse_from(["aoc2024", "run", "3", "--part", "3"]);
+        assert!(result.is_err());
+
+        let result = Cli::try_parse_from(["aoc2024", "run", "3", "--stdin", "--download"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_example_names() {
+        let example = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Command::All { days, example, .. } => (days, input_kind(example)),
+            command => panic!("unexpected command {command:?}"),
+        };
+        assert_eq!(
+            example(&["aoc2024", "all", "--example", "1..=3"]),
+            ("1..=3".to_string(), InputKind::example())
+        );
+        assert_eq!(
+            example(&["aoc2024", "all", "--example=e2"]),
+            ("all".to_string(), InputKind::Example("e2".to_string()))
+        );
+        assert_eq!(example(&["aoc2024", "all"]).1, InputKind::Real);
+    }
+
+    #[test]
+    fn test_params() {
+        let cli = Cli::try_parse_from(["aoc2024", "run", "14", "--param", "width=11"]).unwrap();
+        let Command::Run { params, .. } = cli.command else {
+            panic!("expected the run command");
+        };
+        assert_eq!(params.params().get::<u32>("width").unwrap(), 11);
+
+        assert!(Cli::try_parse_from(["aoc2024", "run", "14", "--param", "width"]).is_err());
+    }
+
+    #[test]
+    fn test_animate_args() {
+        let cli = Cli::try_parse_from(["aoc2024", "run", "6", "--animate", "--fps", "5"]).unwrap();
+        let Command::Run { animate, .. } = cli.command else {
+            panic!("expected the run command");
+        };
+        assert!(animate.animate);
+        assert_eq!(animate.fps, 5);
+
+        assert!(Cli::try_parse_from(["aoc2024", "run", "6", "--animate", "--stdin"]).is_err());
+        assert!(Cli::try_parse_from(["aoc2024", "run", "6", "--fps", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_render_args() {
+        let cli =
+            Cli::try_parse_from(["aoc2024", "render", "14", "--image-format", "png"]).unwrap();
+        let Command::Render { frames, .. } = cli.command else {
+            panic!("expected the render command");
+        };
+        assert_eq!(frames.image_format, ImageFormat::Png);
+        assert_eq!(frames.out, PathBuf::from("frames"));
+
+        let result = Cli::try_parse_from(["aoc2024", "render", "14", "--image-format", "gif"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_gen_args() {
+        let cli =
+            Cli::try_parse_from(["aoc2024", "gen", "9", "--seed", "42", "--size", "10"]).unwrap();
+        let Command::Gen {
+            day,
+            seed,
+            size,
+            output,
+        } = cli.command
+        else {
+            panic!("expected the gen command");
+        };
+        assert_eq!((day, seed, size, output), (9, 42, 10, None));
+
+        assert!(Cli::try_parse_from(["aoc2024", "gen", "9", "--size", "0"]).is_err());
+    }
+}
@@ -1,49 +1,64 @@
 use std::collections::HashMap;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 1");
-    let data = input(1, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = (Vec<u64>, Vec<u64>);
+
+    const DAY: u16 = 1;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(sort(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let (left, right) = sort(input);
-    let dist = distances(left, right);
-    let total: u64 = dist.into_iter().sum();
-    println!("Sum: {total}");
+fn p1((left, right): &(Vec<u64>, Vec<u64>)) -> u64 {
+    let dist = distances(left.clone(), right.clone());
+    dist.into_iter().sum()
 }
 
-fn p2(input: &str) {
-    let (left, right) = sort(input);
-    let ss: u64 = similarity_scores(left, right).iter().sum();
-    println!("Similary Score: {ss}");
+fn p2((left, right): &(Vec<u64>, Vec<u64>)) -> u64 {
+    similarity_scores(left.clone(), right.clone()).iter().sum()
 }
 
-fn sort(input: &str) -> (Vec<u64>, Vec<u64>) {
+fn sort(input: &str) -> Result<(Vec<u64>, Vec<u64>), ParseError> {
     let mut left = Vec::new();
     let mut right = Vec::new();
 
-    for line in input.lines() {
-        let (l, r) = line.split_once(' ').unwrap();
+    for line in Span::new(input).lines() {
+        let (l, r) = line.split_once(" ")?;
         let l = l.trim();
         let r = r.trim();
-        left.push(l.parse().unwrap());
-        right.push(r.parse().unwrap());
+        left.push(l.parse()?);
+        right.push(r.parse()?);
     }
 
     left.sort();
     right.sort();
-    (left, right)
+    Ok((left, right))
 }
 
 fn distances(left: Vec<u64>, right: Vec<u64>) -> Vec<u64> {
     let mut d = Vec::new();
-    for (l, r) in left.into_iter().zip(right.into_iter()) {
+    for (l, r) in left.into_iter().zip(right) {
         let min = l.min(r);
         let max = l.max(r);
         d.push(max - min);
@@ -66,3 +81,21 @@ fn similarity_scores(left: Vec<u64>, right: Vec<u64>) -> Vec<u64> {
 
     ss
 }
+
+/// Two columns of five digit location ids, where some of the right ones also turn up on the left
+/// so that part 2 has similarities to find.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let left = (0..1000 * size)
+        .map(|_| rng.range(10000..100000))
+        .collect::<Vec<_>>();
+    left.iter()
+        .map(|l| {
+            let r = if rng.chance(0.3) {
+                *rng.choose(&left)
+            } else {
+                rng.range(10000..100000)
+            };
+            format!("{l}   {r}\n")
+        })
+        .collect()
+}
@@ -1,40 +1,57 @@
 #![allow(dead_code)]
 #![allow(clippy::all)]
-use crate::utils::input;
+use itertools::Itertools;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 2");
-    let data = input(2, example)?;
-    p1(&data);
-    p2(&data);
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-    Ok(())
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Vec<Vec<i64>>;
+
+    const DAY: u16 = 2;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(to_lists(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let lists = to_lists(input);
-    let safe = lists.iter().filter(|s| State::ingest(s).is_valid()).count();
-    println!("P1: {safe}");
+fn p1(lists: &[Vec<i64>]) -> usize {
+    lists.iter().filter(|s| State::ingest(s).is_valid()).count()
 }
 
-fn p2(input: &str) {
-    let lists = to_lists(input);
-    let safe = lists.iter().filter(|s| test_fully_valid(s)).count();
-    println!("P2: {safe}");
+fn p2(lists: &[Vec<i64>]) -> usize {
+    lists.iter().filter(|s| test_fully_valid(s)).count()
 }
 
-fn to_lists(input: &str) -> Vec<Vec<i64>> {
+fn to_lists(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
     let mut l = Vec::new();
 
-    for line in input.lines() {
+    for line in Span::new(input).lines() {
         l.push(
             line.split_whitespace()
-                .map(|s| s.trim().parse().unwrap())
-                .collect(),
+                .map(|s| s.trim().parse())
+                .collect::<Result<_, _>>()?,
         );
     }
 
-    l
+    Ok(l)
 }
 
 fn in_range(l: i64, r: i64) -> bool {
@@ -98,6 +115,26 @@ impl State {
     }
 }
 
+/// Reports that steadily rise or fall, about half of them spoiled by one bad level.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..1000 * size)
+        .map(|_| {
+            let step = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level = rng.range(30..70);
+            let mut levels = Vec::new();
+            for _ in 0..rng.range(5..9) {
+                levels.push(level);
+                level += step * rng.range(1..4);
+            }
+            if rng.chance(0.5) {
+                let i = rng.index(levels.len());
+                levels[i] += rng.range(-4..5);
+            }
+            format!("{}\n", levels.iter().join(" "))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
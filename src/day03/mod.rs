@@ -1,60 +1,76 @@
 use regex::Regex;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 3");
-    let data = input(3, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Vec<Ins>;
+
+    const DAY: u16 = 3;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Ins::parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let ins = Ins::parse(input);
-    let total: u64 = ins.iter().map(Ins::eval).sum();
-    println!("Part 1: {total}");
+fn p1(ins: &[Ins]) -> u64 {
+    ins.iter().map(Ins::eval).sum()
 }
 
-fn p2(input: &str) {
-    let total = Machine::run(input);
-    println!("Part 2: {total}");
+fn p2(ins: &[Ins]) -> u64 {
+    Machine(ins.to_vec()).run()
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
-enum Ins {
+pub enum Ins {
     Mul(u64, u64),
     Dont,
     Do,
 }
 
 impl Ins {
-    fn parse(text: &str) -> Vec<Self> {
+    fn parse(text: &str) -> Result<Vec<Self>, ParseError> {
         let r = Regex::new(r"^mul\((\d+),(\d+)\)|^do\(\)|^don't\(\)").unwrap();
         let mut ins = Vec::new();
 
-        let mut text = text;
-        while !text.is_empty() {
-            match r.captures(text) {
+        let mut text = Span::new(text);
+        while let Some(ch) = text.as_str().chars().next() {
+            match text.captures(&r) {
                 Some(caps) => {
-                    let full = &caps[0];
+                    let full = caps.get(0).map_or("", |m| m.as_str());
                     if full.starts_with("mul") {
-                        let n1: u64 = caps[1].parse().unwrap();
-                        let n2: u64 = caps[2].parse().unwrap();
+                        let n1: u64 = caps.parse(1)?;
+                        let n2: u64 = caps.parse(2)?;
                         ins.push(Ins::Mul(n1, n2));
                     } else if full.starts_with("don't") {
                         ins.push(Ins::Dont);
                     } else if full.starts_with("do") {
                         ins.push(Ins::Do);
                     }
-                    text = &text[full.len()..];
+                    text = text.skip(full.len());
                 }
-                None => text = &text[1..],
+                None => text = text.skip(ch.len_utf8()),
             }
         }
 
-        ins
+        Ok(ins)
     }
 
     fn eval(&self) -> u64 {
@@ -68,13 +84,7 @@ impl Ins {
 struct Machine(Vec<Ins>);
 
 impl Machine {
-    fn run(input: &str) -> u64 {
-        let ins = Ins::parse(input);
-        let machine = Machine(ins);
-        machine._run()
-    }
-
-    fn _run(self) -> u64 {
+    fn run(self) -> u64 {
         let mut sum = 0u64;
         let mut exe = true;
         for ins in self.0 {
@@ -89,20 +99,44 @@ impl Machine {
     }
 }
 
+/// Lines of corrupted memory: instructions hidden among broken ones and random symbols.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    const JUNK: &[&str] = &[
+        "mul(", "mul[", "mul (", "mul(4*", "mul(6,9!", "do", "don't", "select()", "where()",
+        "from()", "how()", "what()", "who()", "why()", "(", ")", "[", "]", "{", "}", ",", " ", "'",
+        "%", "@", "^", "*", "<", ">", "?", "!", "#", "$", "&", "+", "-", "/", ":", ";", "~",
+    ];
+    (0..6 * size)
+        .map(|_| {
+            let mut line = String::new();
+            while line.len() < 3000 {
+                match rng.below(10) {
+                    0..=2 => line += &format!("mul({},{})", rng.range(1..1000), rng.range(1..1000)),
+                    3 if rng.chance(0.5) => line += "do()",
+                    3 => line += "don't()",
+                    _ => line += JUNK[rng.index(JUNK.len())],
+                }
+            }
+            line + "\n"
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_ins_parse() {
-        assert_eq!(Ins::parse("mul(3,4)"), &[Ins::Mul(3, 4)]);
-        assert_eq!(Ins::parse("%mul(3,4)"), &[Ins::Mul(3, 4)]);
+        assert_eq!(Ins::parse("mul(3,4)").unwrap(), &[Ins::Mul(3, 4)]);
+        assert_eq!(Ins::parse("%mul(3,4)").unwrap(), &[Ins::Mul(3, 4)]);
         assert_eq!(
-            Ins::parse("%mul(3,4)&Mul(4,5)mul(7,6)"),
+            Ins::parse("%mul(3,4)&Mul(4,5)mul(7,6)").unwrap(),
             &[Ins::Mul(3, 4), Ins::Mul(7, 6)]
         );
         assert_eq!(
-            Ins::parse("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"),
+            Ins::parse("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))")
+                .unwrap(),
             &[
                 Ins::Mul(2, 4),
                 Ins::Dont,
@@ -113,4 +147,11 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_ins_parse_overflow() {
+        let error = Ins::parse("do()\nxmul(99999999999999999999,2)").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.text, "99999999999999999999");
+    }
 }
@@ -1,187 +1,146 @@
 #![allow(clippy::all)]
-use crate::utils::input;
+use crate::{
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{scaled_side, Rng},
+        Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 4");
-    let data = input(4, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
-
-fn p1(input: &str) {
-    let input = parse_input(input);
-    let count = find_xmas_count(&input);
-    println!("P1: {count:?}");
-}
-
-fn p2(input: &str) {
-    let input = parse_input(input);
-    let count = find_x_mas_count(&input);
-    println!("P2: {count:?}");
-}
-
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect()
-}
+impl Solution for Day {
+    type Input = Grid<char>;
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl Coord {
-    fn new(x: usize, y: usize) -> Coord {
-        Coord { x, y }
-    }
+    const DAY: u16 = 4;
 
-    fn in_input(&self, input: &Vec<Vec<char>>) -> Option<char> {
-        input.get(self.y)?.get(self.x).copied()
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Grid::parse(input, |_, ch| Some(ch))?)
     }
 
-    fn offset_horz(&self, offset: usize) -> Coord {
-        Coord::new(self.x + offset, self.y)
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(find_xmas_count(input).into())
     }
 
-    fn offset_vert(&self, offset: usize) -> Coord {
-        Coord::new(self.x, self.y + offset)
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(find_x_mas_count(input).into())
     }
 
-    fn offset_diag_down(&self, offset: usize) -> Coord {
-        Coord::new(self.x + offset, self.y + offset)
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        frames.frame(&render(input))
     }
 
-    fn offset_diag_up(&self, offset: usize) -> Option<Coord> {
-        Some(Coord::new(self.x + offset, self.y.checked_sub(offset)?))
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
     }
+}
 
-    fn search_set(&self) -> Vec<CoordSet> {
-        let mut set = Vec::new();
-        set.push(CoordSet(
-            *self,
-            self.offset_horz(1),
-            self.offset_horz(2),
-            self.offset_horz(3),
-        ));
-        set.push(CoordSet(
-            *self,
-            self.offset_vert(1),
-            self.offset_vert(2),
-            self.offset_vert(3),
-        ));
-        set.push(CoordSet(
-            *self,
-            self.offset_diag_down(1),
-            self.offset_diag_down(2),
-            self.offset_diag_down(3),
-        ));
-
-        // Diagonal up may not exist
-        let mas = self
-            .offset_diag_up(1)
-            .and_then(|m| Some((m, self.offset_diag_up(2)?)))
-            .and_then(|(m, a)| Some((m, a, self.offset_diag_up(3)?)));
-        if let Some((m, a, s)) = mas {
-            set.push(CoordSet(*self, m, a, s));
-        }
-        set
-    }
+/// The directions a word can run in. The other four are the same words backwards.
+const DIRECTIONS: [Vec2; 4] = [
+    Vec2 { x: 1, y: 0 },
+    Vec2 { x: 0, y: 1 },
+    Vec2 { x: 1, y: 1 },
+    Vec2 { x: 1, y: -1 },
+];
+
+/// Every four-letter line starting at `pos`.
+fn search_set(pos: Vec2) -> [CoordSet; 4] {
+    DIRECTIONS.map(|dir| CoordSet(pos, pos + dir, pos + dir * 2, pos + dir * 3))
+}
 
-    fn x_search_set(&self) -> Option<Vec<XCoordSet>> {
-        let a = XCoordSet(*self, self.offset_diag_down(1), self.offset_diag_down(2));
-        let b = Some(self.offset_vert(2))
-            .and_then(|m| Some((m, m.offset_diag_up(1)?)))
-            .and_then(|(m, a)| Some((m, a, a.offset_diag_up(1)?)))
-            .map(|s| XCoordSet(s.0, s.1, s.2))?;
-        Some(vec![a, b])
-    }
+/// The two diagonals of the three by three square whose top left corner is `pos`.
+fn x_search_set(pos: Vec2) -> [XCoordSet; 2] {
+    let down = Vec2 { x: 1, y: 1 };
+    let up = Vec2 { x: 1, y: -1 };
+    let bottom = pos + Vec2 { x: 0, y: 2 };
+    [
+        XCoordSet(pos, pos + down, pos + down * 2),
+        XCoordSet(bottom, bottom + up, bottom + up * 2),
+    ]
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
-struct CoordSet(Coord, Coord, Coord, Coord);
+struct CoordSet(Vec2, Vec2, Vec2, Vec2);
 
 impl CoordSet {
-    fn in_input(&self, input: &Vec<Vec<char>>) -> Option<(char, char, char, char)> {
-        let x = self.0.in_input(input)?;
-        let m = self.1.in_input(input)?;
-        let a = self.2.in_input(input)?;
-        let s = self.3.in_input(input)?;
+    fn in_input(&self, input: &Grid<char>) -> Option<(char, char, char, char)> {
+        let x = *input.get(self.0)?;
+        let m = *input.get(self.1)?;
+        let a = *input.get(self.2)?;
+        let s = *input.get(self.3)?;
         Some((x, m, a, s))
     }
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
-struct XCoordSet(Coord, Coord, Coord);
+struct XCoordSet(Vec2, Vec2, Vec2);
 
 impl XCoordSet {
-    fn in_input(&self, input: &Vec<Vec<char>>) -> Option<(char, char, char)> {
-        let m = self.0.in_input(input)?;
-        let a = self.1.in_input(input)?;
-        let s = self.2.in_input(input)?;
+    fn in_input(&self, input: &Grid<char>) -> Option<(char, char, char)> {
+        let m = *input.get(self.0)?;
+        let a = *input.get(self.1)?;
+        let s = *input.get(self.2)?;
         Some((m, a, s))
     }
 }
 
-fn find_xmas_count(input: &Vec<Vec<char>>) -> usize {
-    let mut count = 0;
-    let mut elim_board = vec![vec!['.'; input[0].len()]; input.len()];
-
-    for y in 0..input.len() {
-        for x in 0..input[0].len() {
-            let coord = Coord::new(x, y);
-            let sets = coord.search_set();
-            for set in sets {
-                let word = set.in_input(input);
-                match word {
-                    Some(('X', 'M', 'A', 'S')) | Some(('S', 'A', 'M', 'X')) => {
-                        elim_board[set.0.y][set.0.x] = set.0.in_input(input).unwrap();
-                        elim_board[set.1.y][set.1.x] = set.1.in_input(input).unwrap();
-                        elim_board[set.2.y][set.2.x] = set.2.in_input(input).unwrap();
-                        elim_board[set.3.y][set.3.x] = set.3.in_input(input).unwrap();
-                        count += 1
-                    }
-                    _ => {}
-                };
-            }
+fn find_xmas_count(input: &Grid<char>) -> usize {
+    find_xmas(input).len()
+}
+
+/// Every XMAS in the grid, forwards or backwards.
+fn find_xmas(input: &Grid<char>) -> Vec<CoordSet> {
+    let mut found = Vec::new();
+
+    for pos in input.positions() {
+        for set in search_set(pos) {
+            let word = set.in_input(input);
+            match word {
+                Some(('X', 'M', 'A', 'S')) | Some(('S', 'A', 'M', 'X')) => found.push(set),
+                _ => {}
+            };
         }
     }
-    print_board(&elim_board);
 
-    count
+    found
 }
 
-fn find_x_mas_count(input: &Vec<Vec<char>>) -> usize {
+fn find_x_mas_count(input: &Grid<char>) -> usize {
     let mut count = 0;
 
-    for y in 0..input.len() {
-        for x in 0..input[0].len() {
-            let coord = Coord::new(x, y);
-            let sets = match coord.x_search_set() {
-                Some(sets) => sets,
-                None => continue,
-            };
-
-            let all = sets.into_iter().all(|set| {
-                let word = set.in_input(input);
-                matches!(word, Some(('M', 'A', 'S') | ('S', 'A', 'M')))
-            });
+    for pos in input.positions() {
+        let all = x_search_set(pos).into_iter().all(|set| {
+            let word = set.in_input(input);
+            matches!(word, Some(('M', 'A', 'S') | ('S', 'A', 'M')))
+        });
 
-            if all {
-                count += 1;
-            }
+        if all {
+            count += 1;
         }
     }
     count
 }
 
-fn print_board(input: &Vec<Vec<char>>) {
-    for line in input {
-        let s = line.iter().collect::<String>();
-        println!("{s}");
+/// The word search with every letter that's part of an XMAS lit up.
+fn render(input: &Grid<char>) -> Image {
+    let mut image = Image::filled(input.width(), input.height(), Rgb::BLACK);
+    for set in find_xmas(input) {
+        for pos in [set.0, set.1, set.2, set.3] {
+            let color = match input[pos] {
+                'X' => Rgb::RED,
+                'M' => Rgb::YELLOW,
+                'A' => Rgb::GREEN,
+                _ => Rgb::BLUE,
+            };
+            image.set(pos, color);
+        }
     }
+    image
+}
+
+/// A square of random letters from XMAS.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = scaled_side(140, size);
+    Grid::from_fn(side, side, |_| *rng.choose(&['X', 'M', 'A', 'S'])).to_string()
 }
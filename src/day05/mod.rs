@@ -1,19 +1,39 @@
 #![allow(clippy::all)]
 use std::collections::{HashMap, HashSet};
 
-use crate::utils::input;
+use itertools::Itertools;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 5");
-    let data = input(5, example)?;
-    p1(&data);
-    p2(&data);
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-    Ok(())
+pub struct Day;
+
+impl Solution for Day {
+    type Input = (Sorter, Vec<Vec<i64>>);
+
+    const DAY: u16 = 5;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, (sorter, updates): &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(sorter, updates).into())
+    }
+
+    fn part2(&self, (sorter, updates): &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(sorter, updates).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let (sorter, updates) = parse(input);
+fn p1(sorter: &Sorter, updates: &[Vec<i64>]) -> i64 {
     let mut sum = 0;
     for (_idx, update) in updates.iter().enumerate() {
         let sorted = sorter.sort(update);
@@ -22,11 +42,10 @@ fn p1(input: &str) {
             sum += update[midpoint];
         }
     }
-    println!("P1: {sum}");
+    sum
 }
 
-fn p2(input: &str) {
-    let (sorter, updates) = parse(input);
+fn p2(sorter: &Sorter, updates: &[Vec<i64>]) -> i64 {
     let mut sum = 0;
     for (_idx, update) in updates.iter().enumerate() {
         let sorted = sorter.sort(update);
@@ -35,31 +54,33 @@ fn p2(input: &str) {
             sum += sorted[midpoint];
         }
     }
-    println!("P2: {sum}");
+    sum
 }
 
-fn parse(input: &str) -> (Sorter, Vec<Vec<i64>>) {
+fn parse(input: &str) -> Result<(Sorter, Vec<Vec<i64>>), ParseError> {
     let mut pairs: HashMap<i64, HashSet<i64>> = HashMap::new();
     let mut updates: Vec<Vec<i64>> = Vec::new();
 
-    for line in input.lines() {
-        if line.contains('|') {
-            let (left, right) = line.split_once('|').unwrap();
-            let left = left.parse().unwrap();
-            let right = right.parse().unwrap();
+    for line in Span::new(input).lines() {
+        if line.as_str().contains('|') {
+            let (left, right) = line.split_once("|")?;
+            let left = left.parse()?;
+            let right = right.parse()?;
             pairs.entry(left).or_default().insert(right);
-        }
-
-        if line.contains(',') {
-            updates.push(line.split(',').map(|l| l.parse().unwrap()).collect());
+        } else if !line.is_empty() {
+            updates.push(
+                line.split(",")
+                    .map(|l| l.parse())
+                    .collect::<Result<_, _>>()?,
+            );
         }
     }
 
-    (Sorter { pairs }, updates)
+    Ok((Sorter { pairs }, updates))
 }
 
 #[derive(Default, Debug)]
-struct Sorter {
+pub struct Sorter {
     pairs: HashMap<i64, HashSet<i64>>,
 }
 
@@ -88,3 +109,32 @@ impl Sorter {
         sorted
     }
 }
+
+/// Rules for every pair of pages, all taken from one hidden order so that every update has exactly
+/// one correct order, followed by updates of which about half are in that order.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut pages = (10..100).collect_vec();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+
+    let mut rules = pages
+        .iter()
+        .tuple_combinations()
+        .map(|(before, after)| format!("{before}|{after}\n"))
+        .collect_vec();
+    rng.shuffle(&mut rules);
+
+    let updates = (0..200 * size)
+        .map(|_| {
+            let mut update = pages.clone();
+            rng.shuffle(&mut update);
+            update.truncate(2 * rng.index(10) + 5);
+            if rng.chance(0.5) {
+                update.sort_by_key(|page| pages.iter().position(|p| p == page));
+            }
+            format!("{}\n", update.iter().join(","))
+        })
+        .collect::<String>();
+
+    format!("{}\n{updates}", rules.concat())
+}
@@ -2,159 +2,226 @@
 #![allow(unused_variables)]
 use std::collections::HashSet;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Params, Solution},
+    utils::{
+        parallel,
+        rng::{scaled_side, Rng},
+        Dir, Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 6");
-    let data = input(6, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+impl Solution for Day {
+    type Input = (Grid<char>, Player);
 
-fn p1(input: &str) {
-    let (board, mut player) = parse(input);
-    let mut cs = HashSet::new();
-    cs.insert(player);
-    while let Some(next_player) = player.next(&board) {
-        cs.insert(next_player);
-        player = next_player;
+    const DAY: u16 = 6;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, (board, player): &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(board, *player)
+            .ok_or(anyhow::anyhow!("The guard never leaves the lab"))?
+            .into())
+    }
+
+    fn part2(&self, (board, player): &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(board, *player).into())
+    }
+
+    fn render(
+        &self,
+        (board, player): &Self::Input,
+        frames: &mut dyn FrameSink,
+    ) -> anyhow::Result<()> {
+        render(board, *player, frames)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
     }
-    let coords = cs.into_iter().map(|p| p.coord).collect::<HashSet<_>>();
-    println!("P1: {}", coords.len());
 }
 
-fn p2(input: &str) {
-    let (board, init_player) = parse(input);
-    let mut os: HashSet<Coord> = HashSet::new();
-    for (y, line) in board.iter().enumerate() {
-        for (x, _) in line.iter().copied().enumerate() {
-            let c = Coord { x, y };
-            let nc = c.from_board(&board);
-
-            // If the current spot is empty, then it's a possible spot for a new obstruction
-            if matches!(Some('.'), nc) {
-                // Create a new board where the current spot is an obstruction
-                let mut board = board.clone();
-                board[y][x] = '#';
-                let mut player = init_player;
-                let mut cs = HashSet::new();
-                cs.insert(player);
-                while let Some(next_player) = player.next(&board) {
-                    // If the current player and direction has already been visited, then we are in
-                    // a loop and can mark this spot as valid, then stop
-                    if cs.contains(&next_player) {
-                        os.insert(c);
-                        break;
-                    }
-                    cs.insert(next_player);
-                    player = next_player;
-                }
-            }
+fn p1(board: &Grid<char>, mut player: Player) -> Option<usize> {
+    let mut cs = HashSet::new();
+    cs.insert(player);
+    while let Some(next_player) = player.next(board) {
+        // Back somewhere they've already been facing the same way, so they'll go round forever.
+        if !cs.insert(next_player) {
+            return None;
         }
+        player = next_player;
     }
-    println!("P2: {}", os.len());
+    let coords = cs.into_iter().map(|p| p.pos).collect::<HashSet<_>>();
+    Some(coords.len())
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Default, Copy, Eq)]
-pub struct Coord {
-    x: usize,
-    y: usize,
+fn p2(board: &Grid<char>, init_player: Player) -> usize {
+    // Every empty spot is a possible spot for a new obstruction
+    let candidates = board
+        .iter()
+        .filter(|(_, &ch)| ch == '.')
+        .map(|(pos, _)| pos)
+        .collect::<Vec<_>>();
+    parallel::map(&candidates, |&pos| {
+        loops_with_obstruction(board, init_player, pos)
+    })
+    .into_iter()
+    .filter(|&loops| loops)
+    .count()
 }
 
-impl Coord {
-    fn next(&self, dir: &Direction) -> Option<Coord> {
-        Some(match dir {
-            Direction::Up => Coord {
-                x: self.x,
-                y: self.y.checked_sub(1)?,
-            },
-            Direction::Down => Coord {
-                x: self.x,
-                y: self.y + 1,
-            },
-            Direction::Left => Coord {
-                x: self.x.checked_sub(1)?,
-                y: self.y,
-            },
-            Direction::Right => Coord {
-                x: self.x + 1,
-                y: self.y,
-            },
-        })
+fn loops_with_obstruction(board: &Grid<char>, init_player: Player, obstruction: Vec2) -> bool {
+    // Create a new board where the current spot is an obstruction
+    let mut board = board.clone();
+    board[obstruction] = '#';
+    let mut player = init_player;
+    let mut cs = HashSet::new();
+    cs.insert(player);
+    while let Some(next_player) = player.next(&board) {
+        // If the current player and direction has already been visited, then we are in
+        // a loop and can mark this spot as valid, then stop
+        if cs.contains(&next_player) {
+            return true;
+        }
+        cs.insert(next_player);
+        player = next_player;
     }
+    false
+}
 
-    fn from_board(&self, board: &Vec<Vec<char>>) -> Option<char> {
-        board.get(self.y).and_then(|l| l.get(self.x)).copied()
+/// One frame per step of the guard's patrol, leaving a trail behind them.
+fn render(
+    board: &Grid<char>,
+    mut player: Player,
+    frames: &mut dyn FrameSink,
+) -> anyhow::Result<()> {
+    let mut image = Image::from_grid(
+        board,
+        |_, &ch| if ch == '#' { Rgb::GREY } else { Rgb::BLACK },
+    );
+    image.set(player.pos, Rgb::RED);
+    frames.frame(&image)?;
+    let mut seen = HashSet::from([player]);
+    while let Some(next_player) = player.next(board) {
+        if !seen.insert(next_player) {
+            break;
+        }
+        image.set(player.pos, Rgb::YELLOW);
+        image.set(next_player.pos, Rgb::RED);
+        frames.frame(&image)?;
+        player = next_player;
     }
+    Ok(())
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub struct Player {
-    coord: Coord,
-    direction: Direction,
+    pos: Vec2,
+    dir: Dir,
 }
 
 impl Player {
-    fn next(&self, board: &Vec<Vec<char>>) -> Option<Player> {
-        let nc = self.coord.next(&self.direction)?;
-        match nc.from_board(board) {
-            Some('.') => Some(Player {
-                coord: nc,
-                direction: self.direction,
-            }),
-            Some('#') => self.turn().next(board),
-            Some(_) => unimplemented!(),
-            None => None,
+    /// The guard's next step, turning right at obstructions. A guard walled in on every side
+    /// turns on the spot forever, which is a step back to where they already are.
+    fn next(&self, board: &Grid<char>) -> Option<Player> {
+        let mut player = *self;
+        for _ in 0..4 {
+            let pos = player.pos + player.dir.delta();
+            match board.get(pos) {
+                Some('.') => return Some(Player { pos, ..player }),
+                Some('#') => player = player.turn(),
+                Some(_) => unreachable!("parser only allows . # ^"),
+                None => return None,
+            }
         }
+        Some(*self)
     }
 
     fn turn(&self) -> Player {
         Player {
-            coord: self.coord,
-            direction: self.direction.next(),
+            pos: self.pos,
+            dir: self.dir.turn_right(),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash, Default, Copy, Eq)]
-pub enum Direction {
-    #[default]
-    Up,
-    Down,
-    Left,
-    Right,
+fn parse(input: &str) -> Result<(Grid<char>, Player), ParseError> {
+    let mut player = None;
+    let board = Grid::parse(input, |pos, ch| match ch {
+        '.' | '#' => Some(ch),
+        '^' => {
+            player = Some(Player {
+                pos,
+                dir: Dir::North,
+            });
+            Some('.')
+        }
+        _ => None,
+    })?;
+    let player = player.ok_or_else(|| Span::new(input).end().error("expected a guard ('^')"))?;
+    Ok((board, player))
 }
 
-impl Direction {
-    fn next(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
+/// A lab with obstructions scattered around it and a guard facing up. Of a few hundred places to
+/// start, the guard is put where their patrol is longest while still leading out of the lab, like
+/// it always does in a real input.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = scaled_side(130, size);
+    loop {
+        let mut board = Grid::from_fn(side, side, |_| if rng.chance(0.05) { '#' } else { '.' });
+        let mut best = None;
+        for _ in 0..300 {
+            let pos = Vec2::new(rng.index(side) as isize, rng.index(side) as isize);
+            // A clear space ahead also stops the guard from being walled in where they start.
+            if board[pos] != '.' || board.get(pos.up()) != Some(&'.') {
+                continue;
+            }
+            let mut player = Player {
+                pos,
+                dir: Dir::North,
+            };
+            let mut seen = HashSet::from([player]);
+            while let Some(next_player) = player.next(&board) {
+                if !seen.insert(next_player) {
+                    break;
+                }
+                player = next_player;
+            }
+            if player.next(&board).is_none() && best.is_none_or(|(steps, _)| seen.len() > steps) {
+                best = Some((seen.len(), pos));
+            }
+        }
+        if let Some((_, guard)) = best {
+            board[guard] = '^';
+            return board.to_string();
         }
     }
 }
 
-fn parse(input: &str) -> (Vec<Vec<char>>, Player) {
-    let mut board = Vec::new();
-    let mut player = Player::default();
-    for (y, line) in input.lines().enumerate() {
-        let mut lv = Vec::new();
-        for (x, ch) in line.chars().enumerate() {
-            lv.push(match ch {
-                '.' => '.',
-                '#' => '#',
-                '^' => {
-                    player.coord = Coord { x, y };
-                    '.'
-                }
-                _ => unimplemented!(),
-            });
-        }
-        board.push(lv);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walled_in_guard() {
+        // Blocked on three sides, so the guard turns round and walks out the bottom, unless the
+        // bottom is obstructed too.
+        let (board, player) = parse(".#.\n#^#\n...\n").unwrap();
+        assert_eq!(p1(&board, player), Some(2));
+        assert_eq!(p2(&board, player), 1);
+    }
+
+    #[test]
+    fn test_guard_that_never_leaves() {
+        let input = parse(".#..\n...#\n#^..\n..#.\n").unwrap();
+        let (board, player) = &input;
+        assert_eq!(p1(board, *player), None);
+        assert!(Day.part1(&input).is_err());
     }
-    (board, player)
 }
@@ -1,47 +1,65 @@
 use itertools::{repeat_n, Itertools};
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::{parallel, rng::Rng},
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 7");
-    let data = input(7, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Vec<Equation>;
+
+    const DAY: u16 = 7;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let equations = parse(input);
-    let total = solve_all_solution(&equations, false);
-    println!("P1: {total}");
+fn p1(equations: &[Equation]) -> u64 {
+    solve_all_solution(equations, false)
 }
 
-fn p2(input: &str) {
-    let equations = parse(input);
-    let total = solve_all_solution(&equations, true);
-    println!("P2: {total}");
+fn p2(equations: &[Equation]) -> u64 {
+    solve_all_solution(equations, true)
 }
 
-fn parse(input: &str) -> Vec<Equation> {
-    input.lines().map(Equation::parse).collect()
+fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+    Span::new(input).lines().map(Equation::parse).collect()
 }
 
 #[derive(Debug, Clone, Default)]
-struct Equation {
+pub struct Equation {
     result: u64,
     nums: Vec<u64>,
 }
 
 impl Equation {
-    fn parse(input: &str) -> Equation {
-        let (result, rest) = input.split_once(':').unwrap();
-        let result: u64 = result.parse().unwrap();
+    fn parse(input: Span) -> Result<Equation, ParseError> {
+        let (result, rest) = input.split_once(":")?;
+        let result: u64 = result.parse()?;
         let nums: Vec<u64> = rest
             .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
-        Equation { result, nums }
+            .map(|n| n.parse())
+            .collect::<Result<_, _>>()?;
+        if nums.is_empty() {
+            return Err(rest.error("expected at least one number"));
+        }
+        Ok(Equation { result, nums })
     }
 
     fn op_count(&self) -> usize {
@@ -121,23 +139,59 @@ fn solve_solution(solution: &Vec<EqPart>) -> u64 {
                 val = next.parse().unwrap();
                 op = None;
             }
-            (Some(_), EqPart::Op(_ops)) => unimplemented!(),
+            (Some(_), EqPart::Op(_)) => unreachable!("numbers and operators alternate"),
         }
     }
 
     val
 }
 
-fn solve_all_solution(equations: &Vec<Equation>, with_concat: bool) -> u64 {
-    let mut total: u64 = 0;
-    for equation in equations {
-        for solution in equation.potential_solutions(with_concat) {
-            let result = solve_solution(&solution);
-            if result == equation.result {
-                total += result;
-                break;
+fn solve_all_solution(equations: &[Equation], with_concat: bool) -> u64 {
+    parallel::map(equations, |equation| {
+        equation
+            .potential_solutions(with_concat)
+            .iter()
+            .map(solve_solution)
+            .find(|&result| result == equation.result)
+            .unwrap_or(0)
+    })
+    .into_iter()
+    .sum()
+}
+
+/// Equations of up to a dozen mostly single digit numbers, about half of them true for some
+/// choice of operators. The numbers have at most 18 digits between them, so that no choice of
+/// operators can overflow.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..850 * size)
+        .map(|_| {
+            let count = rng.range(2..13) as usize;
+            let mut nums = Vec::new();
+            let mut digits = 0;
+            while nums.len() < count {
+                let num = if rng.chance(0.7) {
+                    rng.range(1..10)
+                } else {
+                    rng.range(10..1000)
+                } as u64;
+                digits += num.ilog10() + 1;
+                if digits > 18 {
+                    break;
+                }
+                nums.push(num);
             }
-        }
-    }
-    total
+            let result = if rng.chance(0.5) {
+                nums[1..]
+                    .iter()
+                    .fold(nums[0], |acc, &num| match rng.below(3) {
+                        0 => acc + num,
+                        1 => acc * num,
+                        _ => acc * 10u64.pow(num.ilog10() + 1) + num,
+                    })
+            } else {
+                rng.range(1..1_000_000_000_000) as u64
+            };
+            format!("{result}: {}\n", nums.iter().join(" "))
+        })
+        .collect()
 }
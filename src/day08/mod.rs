@@ -2,54 +2,64 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{scaled_side, Rng},
+        Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 8");
-    let data = input(8, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+impl Solution for Day {
+    type Input = Grid<char>;
+
+    const DAY: u16 = 8;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
 
-fn p1(input: &str) {
-    let board = parse(input);
-    let antinodes = find_antinodes(&board, false);
-    println!("P1: {}", antinodes.len());
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p2(input: &str) {
-    let board = parse(input);
-    let antinodes = find_antinodes(&board, true);
-    println!("P1: {}", antinodes.len());
+fn p1(board: &Grid<char>) -> usize {
+    find_antinodes(board, false).len()
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Coord {
-    x: usize,
-    y: usize,
+fn p2(board: &Grid<char>) -> usize {
+    find_antinodes(board, true).len()
 }
 
-fn parse(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|l| l.trim().chars().collect()).collect()
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    let input = input.lines().map(str::trim).join("\n");
+    Grid::parse(&input, |_, ch| Some(ch))
 }
 
-fn find_nodes(board: &[Vec<char>]) -> HashMap<char, HashSet<Coord>> {
-    let mut nodes: HashMap<char, HashSet<Coord>> = HashMap::new();
-    for (y, line) in board.iter().enumerate() {
-        for (x, ch) in line.iter().enumerate() {
-            if *ch == '.' {
-                continue;
-            }
-            let coord = Coord { x, y };
-            nodes.entry(*ch).or_default().insert(coord);
+fn find_nodes(board: &Grid<char>) -> HashMap<char, HashSet<Vec2>> {
+    let mut nodes: HashMap<char, HashSet<Vec2>> = HashMap::new();
+    for (pos, ch) in board.iter() {
+        if *ch == '.' {
+            continue;
         }
+        nodes.entry(*ch).or_default().insert(pos);
     }
     nodes
 }
 
-fn find_antinodes(board: &[Vec<char>], resonance: bool) -> HashSet<Coord> {
+fn find_antinodes(board: &Grid<char>, resonance: bool) -> HashSet<Vec2> {
     let nodes = find_nodes(board);
     let mut antinodes = HashSet::new();
     for (_node, coords) in nodes {
@@ -65,112 +75,38 @@ fn find_antinodes(board: &[Vec<char>], resonance: bool) -> HashSet<Coord> {
     antinodes
 }
 
-fn line_antinodes(board: &[Vec<char>], a: Coord, b: Coord) -> Vec<Coord> {
-    let mut antinodes = Vec::new();
-    let (rise, run) = slope(a, b);
-    {
-        let x = a.x as isize + run;
-        let y = a.y as isize + rise;
-        if is_on_board(board, x, y) {
-            antinodes.push(Coord {
-                x: x as usize,
-                y: y as usize,
-            });
-        }
-    }
-
-    #[allow(clippy::comparison_chain)]
-    let rise = flip_sign(rise);
-
-    #[allow(clippy::comparison_chain)]
-    let run = flip_sign(run);
-
-    {
-        let x = b.x as isize + run;
-        let y = b.y as isize + rise;
-        if is_on_board(board, x, y) {
-            antinodes.push(Coord {
-                x: x as usize,
-                y: y as usize,
-            });
-        }
-    }
-    antinodes
+/// The antinodes are as far beyond each antenna as the antennas are from each other.
+fn line_antinodes(board: &Grid<char>, a: Vec2, b: Vec2) -> Vec<Vec2> {
+    let step = a - b;
+    [a + step, b - step]
+        .into_iter()
+        .filter(|&pos| board.contains(pos))
+        .collect()
 }
 
-fn resonant_line_antinodes(board: &[Vec<char>], a: Coord, b: Coord) -> Vec<Coord> {
-    let mut antinodes = Vec::new();
-
+fn resonant_line_antinodes(board: &Grid<char>, a: Vec2, b: Vec2) -> Vec<Vec2> {
     // All points in the line are now antinodes becuase of resonance, which includes the antenna
     // points.
-    antinodes.extend([a, b]);
-
-    let (rise, run) = slope(a, b);
-
-    let mut a = a;
-    loop {
-        let x = a.x as isize + run;
-        let y = a.y as isize + rise;
-        if is_on_board(board, x, y) {
-            let coord = Coord {
-                x: x as usize,
-                y: y as usize,
-            };
-            antinodes.push(coord);
-            a = coord;
-        } else {
-            break;
-        }
-    }
-
-    #[allow(clippy::comparison_chain)]
-    let rise = flip_sign(rise);
-
-    #[allow(clippy::comparison_chain)]
-    let run = flip_sign(run);
+    let step = (a - b).direction();
+    board
+        .ray(a, step)
+        .chain(board.ray(a, -step))
+        .map(|(pos, _)| pos)
+        .collect()
+}
 
-    let mut b = b;
-    loop {
-        let x = b.x as isize + run;
-        let y = b.y as isize + rise;
-        if is_on_board(board, x, y) {
-            let coord = Coord {
-                x: x as usize,
-                y: y as usize,
-            };
-            antinodes.push(coord);
-            b = coord;
+/// A roof with antennas on about one in twelve spots, on any of 62 frequencies.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let side = scaled_side(50, size);
+    Grid::from_fn(side, side, |_| {
+        if rng.chance(1.0 / 12.0) {
+            *rng.choose(FREQUENCIES) as char
         } else {
-            break;
+            '.'
         }
-    }
-    antinodes
-}
-
-fn slope(a: Coord, b: Coord) -> (isize, isize) {
-    (a.y as isize - b.y as isize, a.x as isize - b.x as isize)
-}
-
-fn is_on_board(board: &[Vec<char>], x: isize, y: isize) -> bool {
-    if x < 0 || y < 0 {
-        return false;
-    }
-
-    let x = x as usize;
-    let y = y as usize;
-
-    y < board.len() && x < board[0].len()
-}
-
-#[allow(clippy::comparison_chain)]
-fn flip_sign(n: isize) -> isize {
-    if n < 0 {
-        n.abs()
-    } else if n > 0 {
-        0 - n
-    } else {
-        n
-    }
+    })
+    .to_string()
 }
 
 #[cfg(test)]
@@ -178,10 +114,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_slope() {
-        let a = Coord { x: 4, y: 3 };
-        let b = Coord { x: 5, y: 5 };
-        assert_eq!(slope(a, b), (-2, -1));
+    fn test_resonant_line_antinodes() {
+        let board = Grid::filled(10, 10, '.');
+        let mut antinodes = resonant_line_antinodes(&board, Vec2::new(2, 4), Vec2::new(0, 0));
+        antinodes.sort();
+        antinodes.dedup();
+        assert_eq!(
+            antinodes,
+            (0..5).map(|n| Vec2::new(n, n * 2)).collect::<Vec<_>>()
+        );
     }
 
     #[test]
@@ -196,16 +137,16 @@ mod tests {
                            ......#...
                            ..........
                            ..........";
-        let board = parse(input);
-        let a = Coord { x: 4, y: 3 };
-        let b = Coord { x: 5, y: 5 };
+        let board = parse(input).unwrap();
+        let a = Vec2::new(4, 3);
+        let b = Vec2::new(5, 5);
         assert_eq!(
             line_antinodes(&board, a, b),
-            vec![Coord { x: 3, y: 1 }, Coord { x: 6, y: 7 }]
+            vec![Vec2::new(3, 1), Vec2::new(6, 7)]
         );
 
-        let a = Coord { x: 5, y: 5 };
-        let b = Coord { x: 8, y: 4 };
-        assert_eq!(line_antinodes(&board, a, b), vec![Coord { x: 2, y: 6 }]);
+        let a = Vec2::new(5, 5);
+        let b = Vec2::new(8, 4);
+        assert_eq!(line_antinodes(&board, a, b), vec![Vec2::new(2, 6)]);
     }
 }
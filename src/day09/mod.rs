@@ -1,43 +1,65 @@
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 9");
-    let data = input(9, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Vec<Option<usize>>;
+
+    const DAY: u16 = 9;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let mut board = parse(input);
+fn p1(board: &[Option<usize>]) -> usize {
+    let mut board = board.to_vec();
     sort_board(&mut board);
-    let cs = checksum(&board);
-    println!("P1: {cs}");
+    checksum(&board)
 }
 
-fn p2(input: &str) {
-    let board = parse(input);
-    let board = sort_board_improved(&board);
-    let result = checksum(&board);
-    println!("P2: {result}");
+fn p2(board: &[Option<usize>]) -> usize {
+    let board = sort_board_improved(board);
+    checksum(&board)
 }
 
-fn parse(input: &str) -> Vec<Option<usize>> {
+fn parse(input: &str) -> Result<Vec<Option<usize>>, ParseError> {
     let mut v = Vec::new();
-    let chars = input.trim().chars().collect_vec();
-    for (id, ch) in chars.chunks(2).enumerate() {
-        let file_blocks = ch[0].to_digit(10).unwrap();
+    let digits = Span::new(input)
+        .trim()
+        .chars()
+        .map(|(span, ch)| {
+            ch.to_digit(10)
+                .ok_or_else(|| span.error("expected a digit"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    for (id, ch) in digits.chunks(2).enumerate() {
+        let file_blocks = ch[0];
         (0..file_blocks).for_each(|_| v.push(Some(id)));
 
-        if let Some(space_blocks) = ch.get(1) {
-            let space_blocks = space_blocks.to_digit(10).unwrap();
+        if let Some(&space_blocks) = ch.get(1) {
             (0..space_blocks).for_each(|_| v.push(None));
         }
     }
-    v
+    Ok(v)
 }
 
 #[allow(dead_code)]
@@ -60,17 +82,21 @@ fn swap<T: Default>(board: &mut [T], first: usize, second: usize) {
 
 fn sort_board(board: &mut [Option<usize>]) {
     loop {
-        let first_space = board
-            .iter()
-            .enumerate()
-            .find_map(|(idx, opt)| if opt.is_none() { Some(idx) } else { None })
-            .unwrap();
-        let last_block = board
-            .iter()
-            .enumerate()
-            .rev()
-            .find_map(|(idx, opt)| if opt.is_some() { Some(idx) } else { None })
-            .unwrap();
+        let first_space =
+            board
+                .iter()
+                .enumerate()
+                .find_map(|(idx, opt)| if opt.is_none() { Some(idx) } else { None });
+        let last_block =
+            board
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(idx, opt)| if opt.is_some() { Some(idx) } else { None });
+        // Nothing to move, or nowhere to move it to
+        let (Some(first_space), Some(last_block)) = (first_space, last_block) else {
+            return;
+        };
         // Sorted!
         if first_space == last_block + 1 {
             return;
@@ -158,13 +184,30 @@ fn checksum(board: &[Option<usize>]) -> usize {
         .sum()
 }
 
+/// A disk map of files of one to nine blocks, with up to nine free blocks between them. Compacting
+/// the disk takes time quadratic in its length, so the number of files grows with the square root
+/// of the size, to keep the time to solve it growing linearly.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let files = (10_000.0 * (size as f64).sqrt()).round() as usize;
+    let mut map = String::new();
+    for file in 0..files {
+        if file > 0 {
+            map.push(char::from(b'0' + rng.below(10) as u8));
+        }
+        map.push(char::from(b'1' + rng.below(9) as u8));
+    }
+    map + "\n"
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     #[test]
     fn test_parse() {
-        let board = parse("2333133121414131402");
+        let board = parse("2333133121414131402").unwrap();
         assert_eq!(
             board_to_string(&board),
             "00...111...2...333.44.5555.6666.777.888899"
@@ -178,6 +221,17 @@ mod tests {
         assert_eq!(board, &[Some(1), None, Some(0)]);
     }
 
+    #[test]
+    fn test_sort_board_without_free_space() {
+        for disk_map in ["", "0", "12", "10203"] {
+            let mut board = parse(disk_map).unwrap();
+            let before = board.clone();
+            sort_board(&mut board);
+            assert_eq!(board, before, "{disk_map:?}");
+        }
+        assert_eq!(p1(&parse("10203").unwrap()), 27);
+    }
+
     #[test]
     fn test_move_file() {
         let result = move_file(&[None, None, None, None], &[Some(1), Some(2)]);
@@ -186,4 +240,34 @@ mod tests {
             (vec![Some(1), Some(2), None, None], vec![None, None])
         );
     }
+
+    /// Where each file's blocks are, by id.
+    fn file_positions(board: &[Option<usize>]) -> std::collections::BTreeMap<usize, Vec<usize>> {
+        let mut files = std::collections::BTreeMap::<usize, Vec<usize>>::new();
+        for (idx, id) in board.iter().enumerate() {
+            if let Some(id) = id {
+                files.entry(*id).or_default().push(idx);
+            }
+        }
+        files
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sort_board_improved(disk_map in "([1-9][0-9]){0,30}[1-9]") {
+            let board = parse(&disk_map).unwrap();
+            let sorted = sort_board_improved(&board);
+            prop_assert_eq!(sorted.len(), board.len());
+
+            let before = file_positions(&board);
+            let after = file_positions(&sorted);
+            prop_assert_eq!(before.keys().collect_vec(), after.keys().collect_vec());
+            for (id, old) in &before {
+                let new = &after[id];
+                prop_assert_eq!(new.len(), old.len(), "file {} changed size", id);
+                prop_assert!(new.windows(2).all(|w| w[0] + 1 == w[1]), "file {} was split", id);
+                prop_assert!(new[0] <= old[0], "file {} moved right", id);
+            }
+        }
+    }
 }
@@ -1,126 +1,84 @@
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{scaled_side, Rng},
+        Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 10");
-    let data = input(10, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Board;
+
+    const DAY: u16 = 10;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Board(parse(input)?))
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let board = Board(parse(input));
-    let result: usize = board
+fn p1(board: &Board) -> usize {
+    board
         .trailheads()
         .into_iter()
         .map(|th| board.find_trails(&th))
         .map(|th| th.iter().unique_by(|path| path.last().copied()).count())
-        .sum();
-    println!("P1: {result:#?}");
+        .sum()
 }
 
-fn p2(input: &str) {
-    let board = Board(parse(input));
-    let result: usize = board
+fn p2(board: &Board) -> usize {
+    board
         .trailheads()
         .into_iter()
         .map(|th| board.find_trails(&th))
         .map(|trails| trails.len())
-        .sum();
-    println!("P2: {result:#?}");
+        .sum()
 }
 
-fn parse(input: &str) -> Vec<Vec<u32>> {
-    input
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect_vec())
-        .collect_vec()
-}
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-
-impl Coord {
-    fn next(&self) -> Vec<Coord> {
-        [
-            self.up(),
-            Some(self.right()),
-            Some(self.down()),
-            self.left(),
-        ]
-        .into_iter()
-        .flatten()
-        .collect_vec()
-    }
-
-    fn up(&self) -> Option<Coord> {
-        Some(Coord {
-            x: self.x,
-            y: self.y.checked_sub(1)?,
-        })
-    }
-
-    fn down(&self) -> Coord {
-        Coord {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-
-    fn left(&self) -> Option<Coord> {
-        Some(Coord {
-            x: self.x.checked_sub(1)?,
-            y: self.y,
-        })
-    }
-
-    fn right(&self) -> Coord {
-        Coord {
-            x: self.x + 1,
-            y: self.y,
-        }
-    }
+fn parse(input: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse(input, |_, c| c.to_digit(10))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Board(Vec<Vec<u32>>);
+pub struct Board(Grid<u32>);
 
 impl Board {
-    fn get(&self, coord: &Coord) -> Option<u32> {
-        self.0.get(coord.y).and_then(|l| l.get(coord.x).copied())
+    fn get(&self, coord: &Vec2) -> Option<u32> {
+        self.0.get(*coord).copied()
     }
 
-    fn trailheads(&self) -> Vec<Coord> {
-        let mut coords = Vec::new();
-        for (y, line) in self.0.iter().enumerate() {
-            for (x, n) in line.iter().enumerate() {
-                if *n == 0 {
-                    coords.push(Coord { x, y });
-                }
-            }
-        }
-        coords
+    fn trailheads(&self) -> Vec<Vec2> {
+        self.0
+            .iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(coord, _)| coord)
+            .collect()
     }
 
-    fn find_trails(&self, trailhead: &Coord) -> Vec<Vec<Coord>> {
-        let next = trailhead.next();
-        next.into_iter()
+    fn find_trails(&self, trailhead: &Vec2) -> Vec<Vec<Vec2>> {
+        self.0
+            .neighbors(*trailhead)
             .flat_map(|n| self.find_next_step(&n, 1, vec![*trailhead]))
             .collect_vec()
     }
 
-    fn find_next_step(
-        &self,
-        coord: &Coord,
-        expected: u32,
-        mut path: Vec<Coord>,
-    ) -> Vec<Vec<Coord>> {
+    fn find_next_step(&self, coord: &Vec2, expected: u32, mut path: Vec<Vec2>) -> Vec<Vec<Vec2>> {
         let value = match self.get(coord) {
             Some(n) => n,
             None => return Vec::new(),
@@ -136,10 +94,36 @@ impl Board {
             return vec![path];
         }
 
-        coord
-            .next()
-            .into_iter()
+        self.0
+            .neighbors(*coord)
             .flat_map(|next| self.find_next_step(&next, value + 1, path.clone()))
             .collect_vec()
     }
 }
+
+/// A map of random heights with trails climbing from 0 to 9 wandering across it. Trails never
+/// cross each other, but can stop short when they run out of room.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = scaled_side(50, size);
+    let mut map = Grid::from_fn(side, side, |_| rng.below(10) as u32);
+    let mut on_trail = Grid::filled(side, side, false);
+    for _ in 0..side * side / 20 {
+        let mut pos = Vec2::new(rng.index(side) as isize, rng.index(side) as isize);
+        for height in 0..10 {
+            if on_trail[pos] {
+                break;
+            }
+            map[pos] = height;
+            on_trail[pos] = true;
+            let next = map
+                .neighbors(pos)
+                .filter(|&next| !on_trail[next])
+                .collect_vec();
+            if next.is_empty() {
+                break;
+            }
+            pos = *rng.choose(&next);
+        }
+    }
+    map.to_string()
+}
@@ -1,40 +1,79 @@
 use std::collections::HashMap;
 
-use crate::utils::input;
+use itertools::Itertools;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 11");
-    let data = input(11, example)?;
-    p1(&data);
-    p2(&data);
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Param, Params, Solution},
+    utils::rng::Rng,
+};
 
-    Ok(())
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Stones;
+
+    const DAY: u16 = 11;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "part1_blinks",
+            default: "25",
+            help: "How many times to blink in part 1",
+        },
+        Param {
+            name: "part2_blinks",
+            default: "75",
+            help: "How many times to blink in part 2",
+        },
+    ];
+
+    fn parse(&self, input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Stones {
+            nums: parse(input)?,
+            part1_blinks: params.get("part1_blinks")?,
+            part2_blinks: params.get("part2_blinks")?,
+        })
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let nums = parse(input);
-    let mut cache = HashMap::new();
-    let result = nums
-        .into_iter()
-        .map(|n| process_stone(n, 25, &mut cache))
-        .sum::<usize>();
-    println!("P1: {result:?}");
+pub struct Stones {
+    nums: Vec<u64>,
+    part1_blinks: usize,
+    part2_blinks: usize,
+}
+
+fn p1(stones: &Stones) -> usize {
+    count_stones(&stones.nums, stones.part1_blinks)
+}
+
+fn p2(stones: &Stones) -> usize {
+    count_stones(&stones.nums, stones.part2_blinks)
 }
 
-fn p2(input: &str) {
-    let nums = parse(input);
+fn count_stones(nums: &[u64], blinks: usize) -> usize {
     let mut cache = HashMap::new();
-    let result = nums
-        .into_iter()
-        .map(|n| process_stone(n, 75, &mut cache))
-        .sum::<usize>();
-    println!("P2: {result:?}");
+    nums.iter()
+        .map(|n| process_stone(*n, blinks, &mut cache))
+        .sum::<usize>()
 }
 
-fn parse(input: &str) -> Vec<u64> {
-    input
+fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    Span::new(input)
         .split_whitespace()
-        .map(|n| n.parse().unwrap())
+        .map(|n| n.parse())
         .collect()
 }
 
@@ -50,7 +89,7 @@ fn process_stone(stone: u64, blinks: usize, cache: &mut HashMap<(u64, usize), us
 
     let val = if stone == 0 {
         process_stone(1, blinks - 1, cache)
-    } else if count_digits(stone) % 2 == 0 {
+    } else if count_digits(stone).is_multiple_of(2) {
         let (left, right) = split_number(stone);
         process_stone(left, blinks - 1, cache) + process_stone(right, blinks - 1, cache)
     } else {
@@ -77,3 +116,79 @@ fn split_number(num: u64) -> (u64, u64) {
 
     (left_half, right_half)
 }
+
+/// A line of stones with numbers of up to seven digits.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let stones = (0..8 * size).map(|_| rng.below(10_000_000)).join(" ");
+    format!("{stones}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utils::rng::Rng;
+
+    #[test]
+    fn test_split_number() {
+        assert_eq!(split_number(1000), (10, 0));
+        assert_eq!(split_number(253000), (253, 0));
+        assert_eq!(split_number(17), (1, 7));
+    }
+
+    /// Blinks at the stones one at a time, keeping every stone in a list, rather than counting
+    /// them with [`process_stone`].
+    fn brute_force_count(nums: &[u64], blinks: usize) -> usize {
+        let mut stones = nums.to_vec();
+        for _ in 0..blinks {
+            stones = stones
+                .into_iter()
+                .flat_map(|stone| {
+                    let digits = stone.to_string();
+                    if stone == 0 {
+                        vec![1]
+                    } else if digits.len().is_multiple_of(2) {
+                        let (left, right) = digits.split_at(digits.len() / 2);
+                        vec![left.parse().unwrap(), right.parse().unwrap()]
+                    } else {
+                        vec![stone * 2024]
+                    }
+                })
+                .collect();
+        }
+        stones.len()
+    }
+
+    #[test]
+    fn test_count_stones_matches_brute_force() {
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let nums = parse(&generate(&mut rng, 1)).unwrap();
+            let blinks = rng.index(20);
+            assert_eq!(
+                count_stones(&nums, blinks),
+                brute_force_count(&nums, blinks),
+                "{nums:?} after {blinks} blinks",
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_split_number_recombines(num in 10u64..u64::MAX) {
+            let digits = count_digits(num);
+            prop_assume!(digits.is_multiple_of(2));
+            let (left, right) = split_number(num);
+            prop_assert_eq!(count_digits(left), digits / 2);
+            prop_assert!(right < 10u64.pow(digits / 2));
+            prop_assert_eq!(left * 10u64.pow(digits / 2) + right, num);
+            prop_assert_eq!(format!("{left}{right:0width$}", width = digits as usize / 2), num.to_string());
+        }
+
+        #[test]
+        fn prop_count_digits(num: u64) {
+            prop_assert_eq!(count_digits(num) as usize, num.to_string().len());
+        }
+    }
+}
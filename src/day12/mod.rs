@@ -1,67 +1,59 @@
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-};
+use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{scaled_side, Rng},
+        Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 12");
-    let data = input(12, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+const UP: Vec2 = Vec2 { x: 0, y: -1 };
+const DOWN: Vec2 = Vec2 { x: 0, y: 1 };
+const LEFT: Vec2 = Vec2 { x: -1, y: 0 };
+const RIGHT: Vec2 = Vec2 { x: 1, y: 0 };
 
-fn p1(input: &str) {
-    let board = parse(input);
-    let cost = calculate_cost(&board);
-    println!("P1: {cost:?}");
-}
+impl Solution for Day {
+    type Input = Grid<char>;
 
-fn p2(input: &str) {
-    let board = parse(input);
-    let cost = calculate_bulk_cost(&board);
-    println!("P2: {cost:?}");
-}
+    const DAY: u16 = 12;
 
-fn parse(input: &str) -> HashMap<Coord, char> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars().enumerate().map(move |(x, ch)| {
-                (
-                    Coord {
-                        x: x as isize,
-                        y: y as isize,
-                    },
-                    ch,
-                )
-            })
-        })
-        .collect()
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Grid::parse(input, |_, ch| Some(ch))?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(calculate_cost(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(calculate_bulk_cost(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn find_regions(board: &HashMap<Coord, char>) -> Vec<HashSet<Coord>> {
-    let mut queue = board.iter().map(|(coord, _)| *coord).collect_vec();
+fn find_regions(board: &Grid<char>) -> Vec<HashSet<Vec2>> {
+    let mut queue = board.positions().collect_vec();
     let mut regions = Vec::new();
     while let Some(coord) = queue.pop() {
-        if !board.contains_key(&coord) {
+        let Some(&ch) = board.get(coord) else {
             continue;
-        }
-        let ch = board[&coord];
+        };
         let mut region = HashSet::new();
         let mut reg_queue = vec![coord];
         while let Some(next_coord) = reg_queue.pop() {
-            if board.get(&next_coord) == Some(&ch) {
+            if board.get(next_coord) == Some(&ch) {
                 region.insert(next_coord);
                 reg_queue.extend(
                     next_coord
-                        .borders()
+                        .neighbors()
                         .into_iter()
                         .filter(|c| !region.contains(c)),
                 );
@@ -73,15 +65,15 @@ fn find_regions(board: &HashMap<Coord, char>) -> Vec<HashSet<Coord>> {
     regions
 }
 
-fn calculate_cost(board: &HashMap<Coord, char>) -> usize {
+fn calculate_cost(board: &Grid<char>) -> usize {
     find_regions(board).iter().map(region_cost).sum()
 }
 
-fn region_cost(region: &HashSet<Coord>) -> usize {
+fn region_cost(region: &HashSet<Vec2>) -> usize {
     region
         .iter()
         .map(|c| {
-            c.borders()
+            c.neighbors()
                 .into_iter()
                 .filter(|c| region.contains(c))
                 .count()
@@ -91,19 +83,19 @@ fn region_cost(region: &HashSet<Coord>) -> usize {
         * region.len()
 }
 
-fn calculate_bulk_cost(board: &HashMap<Coord, char>) -> usize {
+fn calculate_bulk_cost(board: &Grid<char>) -> usize {
     find_regions(board).iter().map(region_bulk_cost).sum()
 }
 
-fn region_bulk_cost(region: &HashSet<Coord>) -> usize {
+fn region_bulk_cost(region: &HashSet<Vec2>) -> usize {
     count_region_sides(region) * region.len()
 }
 
-fn count_region_sides(region: &HashSet<Coord>) -> usize {
-    let minx = region.iter().map(Coord::x).min().unwrap();
-    let maxx = region.iter().map(Coord::x).max().unwrap();
-    let miny = region.iter().map(Coord::y).min().unwrap();
-    let maxy = region.iter().map(Coord::y).max().unwrap();
+fn count_region_sides(region: &HashSet<Vec2>) -> usize {
+    let minx = region.iter().map(|pos| pos.x).min().unwrap();
+    let maxx = region.iter().map(|pos| pos.x).max().unwrap();
+    let miny = region.iter().map(|pos| pos.y).min().unwrap();
+    let maxy = region.iter().map(|pos| pos.y).max().unwrap();
 
     let mut counter = 0;
     let mut visited_top = HashSet::new();
@@ -113,43 +105,43 @@ fn count_region_sides(region: &HashSet<Coord>) -> usize {
 
     for y in miny..=maxy {
         for x in minx..=maxx {
-            let cur_coord = Coord { x, y };
+            let cur_coord = Vec2 { x, y };
 
             let mut coord = cur_coord;
-            if coord.is_top_border(region) && !visited_top.contains(&coord) {
+            if is_border(region, coord, UP) && !visited_top.contains(&coord) {
                 counter += 1;
 
-                while coord.is_top_border(region) {
+                while is_border(region, coord, UP) {
                     visited_top.insert(coord);
                     coord = coord.right();
                 }
             }
 
             coord = cur_coord;
-            if coord.is_bottom_border(region) && !visited_bottom.contains(&coord) {
+            if is_border(region, coord, DOWN) && !visited_bottom.contains(&coord) {
                 counter += 1;
 
-                while coord.is_bottom_border(region) {
+                while is_border(region, coord, DOWN) {
                     visited_bottom.insert(coord);
                     coord = coord.right();
                 }
             }
 
             coord = cur_coord;
-            if coord.is_left_border(region) && !visited_left.contains(&coord) {
+            if is_border(region, coord, LEFT) && !visited_left.contains(&coord) {
                 counter += 1;
 
-                while coord.is_left_border(region) {
+                while is_border(region, coord, LEFT) {
                     visited_left.insert(coord);
                     coord = coord.down();
                 }
             }
 
             coord = cur_coord;
-            if coord.is_right_border(region) && !visited_right.contains(&coord) {
+            if is_border(region, coord, RIGHT) && !visited_right.contains(&coord) {
                 counter += 1;
 
-                while coord.is_right_border(region) {
+                while is_border(region, coord, RIGHT) {
                     visited_right.insert(coord);
                     coord = coord.down();
                 }
@@ -160,68 +152,34 @@ fn count_region_sides(region: &HashSet<Coord>) -> usize {
     counter
 }
 
-#[derive(Default, Debug, Clone, Copy, Hash, PartialEq, Eq)]
-struct Coord {
-    x: isize,
-    y: isize,
+/// Whether `pos` is in the region but its neighbour `side` of it isn't, so the region has a
+/// fence on that side of it.
+fn is_border(region: &HashSet<Vec2>, pos: Vec2, side: Vec2) -> bool {
+    region.contains(&pos) && !region.contains(&(pos + side))
 }
 
-impl Coord {
-    fn borders(&self) -> [Coord; 4] {
-        [self.up(), self.down(), self.left(), self.right()]
-    }
-
-    fn is_top_border(&self, region: &HashSet<Coord>) -> bool {
-        region.contains(self) && !region.contains(&self.up())
-    }
-
-    fn is_bottom_border(&self, region: &HashSet<Coord>) -> bool {
-        region.contains(self) && !region.contains(&self.down())
+/// A garden of plots grown outwards from random seeds, so that the regions come in all shapes,
+/// some of them surrounding others.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = scaled_side(140, size);
+    let mut garden = Grid::filled(side, side, None);
+    let mut frontier = Vec::new();
+    for _ in 0..(side * side / 200).max(1) {
+        let pos = Vec2::new(rng.index(side) as isize, rng.index(side) as isize);
+        garden[pos] = Some(char::from(b'A' + rng.below(26) as u8));
+        frontier.push(pos);
     }
-
-    fn is_left_border(&self, region: &HashSet<Coord>) -> bool {
-        region.contains(self) && !region.contains(&self.left())
-    }
-
-    fn is_right_border(&self, region: &HashSet<Coord>) -> bool {
-        region.contains(self) && !region.contains(&self.right())
-    }
-
-    fn up(&self) -> Coord {
-        Coord {
-            x: self.x,
-            y: self.y - 1,
-        }
-    }
-
-    fn down(&self) -> Coord {
-        Coord {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-
-    fn left(&self) -> Coord {
-        Coord {
-            x: self.x - 1,
-            y: self.y,
-        }
-    }
-
-    fn right(&self) -> Coord {
-        Coord {
-            x: self.x + 1,
-            y: self.y,
+    while !frontier.is_empty() {
+        let pos = frontier.swap_remove(rng.index(frontier.len()));
+        let plant = garden[pos];
+        for next in pos.neighbors() {
+            if garden.get(next) == Some(&None) {
+                garden[next] = plant;
+                frontier.push(next);
+            }
         }
     }
-
-    fn x(&self) -> isize {
-        self.x
-    }
-
-    fn y(&self) -> isize {
-        self.y
-    }
+    garden.map(|plant| plant.unwrap_or('A')).to_string()
 }
 
 #[cfg(test)]
@@ -230,7 +188,7 @@ mod tests {
 
     #[test]
     fn test_region_cost() {
-        let region = (0..4).map(|x| Coord { x, y: 0 }).collect();
+        let region = (0..4).map(|x| Vec2 { x, y: 0 }).collect();
         assert_eq!(region_cost(&region), 40);
     }
 
@@ -259,7 +217,7 @@ mod tests {
                     .flat_map(move |(y, line)| {
                         line.into_iter().enumerate().filter_map(move |(x, n)| {
                             if n == 1 {
-                                Some(Coord {
+                                Some(Vec2 {
                                     x: x as isize,
                                     y: y as isize,
                                 })
@@ -272,10 +230,11 @@ mod tests {
             })
             .collect_vec();
 
-        assert!(Coord { x: 3, y: 0 }.is_right_border(&regions[1]));
-        assert!(Coord { x: 3, y: 0 }.is_top_border(&regions[1]));
-        assert!(Coord { x: 3, y: 0 }.is_bottom_border(&regions[1]));
-        assert!(!Coord { x: 3, y: 0 }.is_left_border(&regions[1]));
+        let pos = Vec2 { x: 3, y: 0 };
+        assert!(is_border(&regions[1], pos, RIGHT));
+        assert!(is_border(&regions[1], pos, UP));
+        assert!(is_border(&regions[1], pos, DOWN));
+        assert!(!is_border(&regions[1], pos, LEFT));
         assert_eq!(count_region_sides(&regions[0]), 4);
         assert_eq!(count_region_sides(&regions[1]), 6);
     }
@@ -1,37 +1,62 @@
 use std::sync::OnceLock;
 
-use itertools::Itertools;
 use regex::Regex;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 13");
-    let data = input(13, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Vec<Machine>;
+
+    const DAY: u16 = 13;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let machines = parse(input, 0);
-    let cost: isize = machines.iter().flat_map(Machine::solve).sum();
-    println!("P1: {cost:?}");
+fn p1(machines: &[Machine]) -> isize {
+    machines.iter().flat_map(Machine::solve).sum()
 }
 
-fn p2(input: &str) {
-    let machines = parse(input, 10000000000000);
-    let cost: isize = machines.iter().flat_map(Machine::solve).sum();
-    println!("P2: {cost:?}");
+fn p2(machines: &[Machine]) -> isize {
+    machines
+        .iter()
+        .map(|m| m.corrected(10000000000000))
+        .flat_map(|m| m.solve())
+        .sum()
 }
 
-fn parse(input: &str, correction: isize) -> Vec<Machine> {
-    input
-        .lines()
-        .tuples()
-        .map(|(a, b, p, _)| Machine::parse(a, b, p, correction))
-        .collect()
+fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
+    let input = Span::new(input);
+    let mut lines = input.lines().filter(|line| !line.is_empty());
+    let mut machines = Vec::new();
+    while let Some(button_a) = lines.next() {
+        let mut next = || {
+            lines
+                .next()
+                .ok_or_else(|| input.end().error("expected another line"))
+        };
+        machines.push(Machine::parse(button_a, next()?, next()?)?);
+    }
+    Ok(machines)
 }
 
 static BUTTON_A: OnceLock<Regex> = OnceLock::new();
@@ -39,43 +64,263 @@ static BUTTON_B: OnceLock<Regex> = OnceLock::new();
 static PRIZE: OnceLock<Regex> = OnceLock::new();
 
 #[derive(Debug, Clone, Copy)]
-struct Machine {
+pub struct Machine {
     a: (isize, isize),
     b: (isize, isize),
     prize: (isize, isize),
 }
 
 impl Machine {
-    fn parse(button_a: &str, button_b: &str, prize: &str, correction: isize) -> Machine {
+    fn parse(button_a: Span, button_b: Span, prize: Span) -> Result<Machine, ParseError> {
         let regex_a =
             BUTTON_A.get_or_init(|| Regex::new(r#"Button A: X\+(\d+), Y\+(\d+)$"#).unwrap());
         let regex_b =
             BUTTON_B.get_or_init(|| Regex::new(r#"Button B: X\+(\d+), Y\+(\d+)$"#).unwrap());
         let regex_p = PRIZE.get_or_init(|| Regex::new(r#"Prize: X=(\d+), Y=(\d+)$"#).unwrap());
 
-        let caps = regex_a.captures(button_a).expect("button A");
-        let a = (caps[1].parse().unwrap(), caps[2].parse().unwrap());
+        let caps = button_a.matched(regex_a)?;
+        let a = (caps.parse(1)?, caps.parse(2)?);
 
-        let caps = regex_b.captures(button_b).expect("button B");
-        let b = (caps[1].parse().unwrap(), caps[2].parse().unwrap());
+        let caps = button_b.matched(regex_b)?;
+        let b = (caps.parse(1)?, caps.parse(2)?);
 
-        let caps = regex_p.captures(prize).expect("prize");
-        let prize = (
-            caps[1].parse::<isize>().unwrap() + correction,
-            caps[2].parse::<isize>().unwrap() + correction,
-        );
+        let caps = prize.matched(regex_p)?;
+        let prize = (caps.parse(1)?, caps.parse(2)?);
 
-        Machine { a, b, prize }
+        Ok(Machine { a, b, prize })
+    }
+
+    fn corrected(&self, correction: isize) -> Machine {
+        Machine {
+            prize: (self.prize.0 + correction, self.prize.1 + correction),
+            ..*self
+        }
     }
 
     fn solve(&self) -> Option<isize> {
-        let b = (self.a.0 * self.prize.1 - self.a.1 * self.prize.0)
-            / (self.a.0 * self.b.1 - self.a.1 * self.b.0);
-        let a = (self.prize.0 - self.b.0 * b) / self.a.0;
+        let (a, b) = self.presses()?;
+        Some(3 * a + b)
+    }
+
+    /// How many times to press A and B to land on the prize, if it can be reached. A solution
+    /// that needs a negative number of presses of either button doesn't count.
+    fn presses(&self) -> Option<(isize, isize)> {
+        let det = self.a.0 * self.b.1 - self.a.1 * self.b.0;
+        let (a, b) = if det == 0 {
+            self.parallel_presses()?
+        } else {
+            let b = (self.a.0 * self.prize.1 - self.a.1 * self.prize.0) / det;
+            // A moves along at least one axis, or the buttons would count as parallel.
+            let a = if self.a.0 != 0 {
+                (self.prize.0 - self.b.0 * b) / self.a.0
+            } else {
+                (self.prize.1 - self.b.1 * b) / self.a.1
+            };
+            (a, b)
+        };
         let f = (self.a.0 * a + self.b.0 * b, self.a.1 * a + self.b.1 * b);
-        if f != self.prize {
+        if f != self.prize || a < 0 || b < 0 {
             return None;
         }
-        Some(3 * a + b)
+        Some((a, b))
+    }
+
+    /// The cheapest presses when both buttons move the claw along the same line, or not at all,
+    /// so there can be many ways to the prize. This only solves along one axis the buttons move
+    /// on, and [`Machine::presses`] checks the other.
+    fn parallel_presses(&self) -> Option<(isize, isize)> {
+        let (a, b, prize) = if (self.a.0, self.b.0) != (0, 0) {
+            (self.a.0, self.b.0, self.prize.0)
+        } else {
+            (self.a.1, self.b.1, self.prize.1)
+        };
+        if a == 0 && b == 0 {
+            return (prize == 0).then_some((0, 0));
+        }
+        // A costs three tokens a press, so unless it moves at least three times as far as B, it's
+        // dearer per step and should be pressed as few times as possible, and otherwise B should.
+        // Past the other button's step the remainders only repeat.
+        if a < 3 * b {
+            (0..=b)
+                .take_while(|n| n * a <= prize)
+                .find(|n| (prize - n * a) % b == 0)
+                .map(|n| (n, (prize - n * a) / b))
+        } else {
+            (0..=a)
+                .take_while(|n| n * b <= prize)
+                .find(|n| (prize - n * b) % a == 0)
+                .map(|n| ((prize - n * b) / a, n))
+        }
+    }
+}
+
+/// Machines whose prizes can be won about half the time.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut machines = Vec::new();
+    while machines.len() < 320 * size {
+        let a = (rng.range(10..100), rng.range(10..100));
+        let b = (rng.range(10..100), rng.range(10..100));
+        let prize = if rng.chance(0.5) {
+            let (presses_a, presses_b) = (rng.range(1..100), rng.range(1..100));
+            (
+                a.0 * presses_a + b.0 * presses_b,
+                a.1 * presses_a + b.1 * presses_b,
+            )
+        } else {
+            (rng.range(1000..20000), rng.range(1000..20000))
+        };
+        machines.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+    machines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::utils::rng::Rng;
+
+    fn button() -> impl Strategy<Value = (isize, isize)> {
+        (0isize..100, 0isize..100)
+    }
+
+    fn machine() -> impl Strategy<Value = Machine> {
+        (button(), button(), (0isize..20_000, 0isize..20_000)).prop_map(|(a, b, prize)| Machine {
+            a,
+            b,
+            prize,
+        })
+    }
+
+    /// Machines whose buttons both move along the same line, or not at all, with the prize on the
+    /// line more often than not.
+    fn parallel_machine() -> impl Strategy<Value = Machine> {
+        (
+            (0isize..20, 0isize..20),
+            0isize..6,
+            0isize..6,
+            0isize..2_000,
+            (0isize..2, 0isize..2),
+        )
+            .prop_map(|(step, a, b, steps, off)| Machine {
+                a: (step.0 * a, step.1 * a),
+                b: (step.0 * b, step.1 * b),
+                prize: (step.0 * steps + off.0, step.1 * steps + off.1),
+            })
+    }
+
+    /// The cheapest way to win the prize, found by trying every number of presses of A that
+    /// doesn't overshoot and working out whether B can make up the rest, rather than with
+    /// [`Machine::presses`]. A button that doesn't move is never worth pressing.
+    fn brute_force_solve(machine: &Machine) -> Option<isize> {
+        let (a, b, prize) = (machine.a, machine.b, machine.prize);
+        let most_a = [(a.0, prize.0), (a.1, prize.1)]
+            .into_iter()
+            .filter(|&(step, _)| step > 0)
+            .map(|(step, prize)| prize / step)
+            .min()
+            .unwrap_or(0);
+        (0..=most_a)
+            .filter_map(|presses_a| {
+                let rest = (prize.0 - a.0 * presses_a, prize.1 - a.1 * presses_a);
+                let presses_b = match b {
+                    (0, 0) => 0,
+                    (0, step) => rest.1 / step,
+                    (step, _) => rest.0 / step,
+                };
+                (presses_b >= 0 && rest == (b.0 * presses_b, b.1 * presses_b))
+                    .then_some(3 * presses_a + presses_b)
+            })
+            .min()
+    }
+
+    #[test]
+    fn test_negative_presses() {
+        // Pressing A -1 times and B 3 times lands on the prize, which can't be done.
+        let machine = Machine {
+            a: (2, 1),
+            b: (1, 2),
+            prize: (1, 5),
+        };
+        assert_eq!(machine.presses(), None);
+        assert_eq!(machine.solve(), None);
+        assert_eq!(brute_force_solve(&machine), None);
+    }
+
+    #[test]
+    fn test_parallel_buttons() {
+        let machine = |a, b, prize| Machine { a, b, prize };
+        // Two presses of A cost more than one of B.
+        assert_eq!(machine((1, 1), (2, 2), (7, 7)).presses(), Some((1, 3)));
+        // But A moving four times as far makes it the cheaper one.
+        assert_eq!(machine((4, 8), (1, 2), (9, 18)).presses(), Some((2, 1)));
+        assert_eq!(machine((1, 1), (2, 2), (7, 8)).presses(), None);
+        assert_eq!(machine((0, 3), (0, 5), (0, 1)).presses(), None);
+        assert_eq!(machine((0, 3), (0, 5), (0, 8)).presses(), Some((1, 1)));
+        assert_eq!(machine((0, 0), (0, 0), (0, 0)).presses(), Some((0, 0)));
+        assert_eq!(machine((0, 0), (0, 0), (1, 0)).presses(), None);
+        // Not parallel, but A never moves along X.
+        assert_eq!(machine((0, 2), (3, 1), (6, 8)).presses(), Some((3, 2)));
+
+        let input = "Button A: X+1, Y+1\nButton B: X+2, Y+2\nPrize: X=7, Y=7\n";
+        assert_eq!(p1(&parse(input).unwrap()), 6);
+    }
+
+    #[test]
+    fn test_solve_matches_brute_force() {
+        for seed in 0..20 {
+            let machines = parse(&generate(&mut Rng::new(seed), 1)).unwrap();
+            for machine in &machines {
+                assert_eq!(machine.solve(), brute_force_solve(machine), "{machine:?}");
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_brute_force(
+            machine in prop_oneof![machine(), parallel_machine()],
+        ) {
+            prop_assert_eq!(machine.solve(), brute_force_solve(&machine));
+        }
+
+        #[test]
+        fn prop_presses_reach_the_prize(machine in machine()) {
+            if let Some((a, b)) = machine.presses() {
+                prop_assert!(a >= 0 && b >= 0);
+                prop_assert_eq!(machine.a.0 * a + machine.b.0 * b, machine.prize.0);
+                prop_assert_eq!(machine.a.1 * a + machine.b.1 * b, machine.prize.1);
+                prop_assert_eq!(machine.solve(), Some(3 * a + b));
+            } else {
+                prop_assert_eq!(machine.solve(), None);
+            }
+        }
+
+        #[test]
+        fn prop_reachable_prizes_are_found(
+            mut machine in machine(),
+            a in 0isize..1000,
+            b in 0isize..1000,
+            correction in 0isize..1_000_000_000_000,
+        ) {
+            machine.prize = (machine.a.0 * a + machine.b.0 * b, machine.a.1 * a + machine.b.1 * b);
+            let found = machine.presses();
+            prop_assert!(found.is_some());
+            if machine.a.0 * machine.b.1 != machine.a.1 * machine.b.0 {
+                // Buttons that aren't parallel only have the one way to the prize.
+                prop_assert_eq!(found, Some((a, b)));
+            } else {
+                prop_assert!(machine.solve().unwrap() <= 3 * a + b);
+            }
+            let corrected = machine.corrected(correction);
+            if let Some((a, b)) = corrected.presses() {
+                prop_assert_eq!(corrected.a.0 * a + corrected.b.0 * b, corrected.prize.0);
+                prop_assert_eq!(corrected.a.1 * a + corrected.b.1 * b, corrected.prize.1);
+            }
+        }
     }
 }
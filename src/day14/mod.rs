@@ -1,66 +1,117 @@
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Param, Params, Solution},
+    utils::{rng::Rng, Grid, Vec2},
+};
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Robots;
+
+    const DAY: u16 = 14;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "width",
+            default: "101",
+            help: "Width of the space the robots move in",
+        },
+        Param {
+            name: "height",
+            default: "103",
+            help: "Height of the space the robots move in",
+        },
+    ];
+
+    fn parse(&self, input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        let size = Vec2::new(params.get("width")?, params.get("height")?);
+        if size.x < 1 || size.y < 1 {
+            anyhow::bail!("The robots need a space of at least 1x1");
+        }
+        Ok(Robots {
+            values: parse(input, size)?,
+            size,
+        })
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
 
-const WIDTH: isize = 101;
-const HEIGHT: isize = 103;
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p2(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("No possible tree found"))
+    }
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 14");
-    let data = input(14, example)?;
-    p1(&data);
-    p2(&data);
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        render(input, frames)
+    }
 
-    Ok(())
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
+}
+
+pub struct Robots {
+    values: Vec<Value>,
+    size: Vec2,
 }
 
-fn p1(input: &str) {
-    let values = parse(input);
-    let values = process_values(values.into_iter(), 100)
-        .flat_map(as_quadrant)
+fn p1(robots: &Robots) -> usize {
+    process_values(robots.values.iter().copied(), 100, robots.size)
+        .flat_map(|pos| as_quadrant(pos, robots.size))
         .counts()
         .values()
-        .fold(1, |acc, value| *value * acc);
-    println!("P1: {values:?}");
+        .fold(1, |acc, value| *value * acc)
 }
 
-fn p2(input: &str) {
-    let mut values = parse(input);
+fn p2(robots: &Robots) -> Option<usize> {
+    let mut values = robots.values.clone();
     for i in 0..10_000 {
-        values = process_values(values.iter().copied(), 1)
+        values = process_values(values.iter().copied(), 1, robots.size)
             .zip(values.iter())
             .map(|(np, (_op, ov))| (np, *ov))
             .collect_vec();
         if possible_tree(&values) {
-            println!("==== {} possible tree", i + 1);
-            print_board(&values);
+            return Some(i + 1);
         }
     }
-    print_board(&values);
+    None
 }
 
-type Pair = (isize, isize);
-type Value = (Pair, Pair);
+/// A robot's position and velocity.
+pub type Value = (Vec2, Vec2);
 
-fn parse(input: &str) -> Vec<Value> {
+/// Robots have to start inside the `size` space.
+fn parse(input: &str, size: Vec2) -> Result<Vec<Value>, ParseError> {
     let r = Regex::new(r#"p=(\d+),(\d+) v=(-?\d+),(-?\d+)"#).unwrap();
-    input
+    Span::new(input)
         .lines()
         .map(|line| {
-            let caps = r.captures(line).unwrap();
-            (
-                (caps[1].parse::<isize>().unwrap(), caps[2].parse().unwrap()),
-                (caps[3].parse().unwrap(), caps[4].parse().unwrap()),
-            )
+            let caps = line.matched(&r)?;
+            let pos = Vec2::new(caps.parse(1)?, caps.parse(2)?);
+            if pos.x >= size.x || pos.y >= size.y {
+                let group = if pos.x >= size.x { 1 } else { 2 };
+                return Err(caps.get(group).unwrap().error(format!(
+                    "expected a position inside the {}x{} space",
+                    size.x, size.y
+                )));
+            }
+            Ok((pos, Vec2::new(caps.parse(3)?, caps.parse(4)?)))
         })
         .collect()
 }
 
-fn as_quadrant((x, y): Pair) -> Option<isize> {
-    let mid_x = WIDTH / 2;
-    let mid_y = HEIGHT / 2;
-    match (x.cmp(&mid_x), y.cmp(&mid_y)) {
+fn as_quadrant(pos: Vec2, size: Vec2) -> Option<isize> {
+    let mid_x = size.x / 2;
+    let mid_y = size.y / 2;
+    match (pos.x.cmp(&mid_x), pos.y.cmp(&mid_y)) {
         (std::cmp::Ordering::Less, std::cmp::Ordering::Less) => Some(1),
         (std::cmp::Ordering::Less, std::cmp::Ordering::Greater) => Some(3),
         (std::cmp::Ordering::Greater, std::cmp::Ordering::Less) => Some(2),
@@ -69,74 +120,117 @@ fn as_quadrant((x, y): Pair) -> Option<isize> {
     }
 }
 
+/// Where every robot is after `seconds`, teleporting to the other side whenever they go off an
+/// edge.
 fn process_values(
     values: impl Iterator<Item = Value>,
     seconds: isize,
-) -> impl Iterator<Item = Pair> {
-    calculate_raw_positions(values, seconds).map(move |pair| teleport(&pair))
-}
-
-fn calculate_raw_positions(
-    values: impl Iterator<Item = Value>,
-    seconds: isize,
-) -> impl Iterator<Item = Pair> {
-    values.map(move |((x, y), (vx, vy))| (x + vx * seconds, y + vy * seconds))
+    size: Vec2,
+) -> impl Iterator<Item = Vec2> {
+    values.map(move |(pos, vel)| (pos + vel * seconds).wrap(size))
 }
 
-fn teleport(pair: &Pair) -> Pair {
-    let (mut x, mut y) = *pair;
-
-    while x < 0 {
-        x += WIDTH;
-    }
-
-    while x >= WIDTH {
-        x -= WIDTH;
-    }
-
-    while y < 0 {
-        y += HEIGHT;
-    }
-
-    while y >= HEIGHT {
-        y -= HEIGHT;
-    }
-
-    (x, y)
-}
-
-type Board = [[char; WIDTH as usize]; HEIGHT as usize];
-
-fn as_board(values: &[Value]) -> Board {
-    let mut board = [['.'; WIDTH as usize]; HEIGHT as usize];
-    for ((px, py), _) in values {
-        board[*py as usize][*px as usize] = '*';
+fn as_board(values: &[Value], size: Vec2) -> Grid<char> {
+    let mut board = Grid::filled(size.x as usize, size.y as usize, '.');
+    for &(pos, _) in values {
+        board[pos.wrap(size)] = '*';
     }
     board
 }
 
-fn print_board(values: &[Value]) {
-    let board = as_board(values);
-
-    for line in board {
-        for ch in line {
-            print!("{ch}");
+/// One frame per second, up to the first one that might be a tree or as far as part 2 looks.
+fn render(robots: &Robots, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+    let mut values = robots.values.clone();
+    for _ in 0..=10_000 {
+        let board = as_board(&values, robots.size);
+        frames.frame(&Image::from_grid(&board, |_, &ch| {
+            if ch == '*' {
+                Rgb::GREEN
+            } else {
+                Rgb::BLACK
+            }
+        }))?;
+        if possible_tree(&values) {
+            break;
         }
-        println!()
+        values = process_values(values.iter().copied(), 1, robots.size)
+            .zip(values.iter())
+            .map(|(np, (_op, ov))| (np, *ov))
+            .collect_vec();
     }
+    Ok(())
 }
 
 fn possible_tree(values: &[Value]) -> bool {
-    let board = as_board(values);
-    for line in board {
-        if line
-            .into_iter()
-            .chunk_by(|d| *d)
-            .into_iter()
-            .any(|(ch, chunk)| ch == '*' && chunk.count() >= 10)
-        {
+    // Sorted by row and then column, so robots side by side in a row end up next to each other.
+    let robots = values
+        .iter()
+        .map(|(pos, _)| (pos.y, pos.x))
+        .sorted()
+        .dedup()
+        .collect_vec();
+    let mut run = 1;
+    for (prev, next) in robots.iter().tuple_windows() {
+        run = if next.0 == prev.0 && next.1 == prev.1 + 1 {
+            run + 1
+        } else {
+            1
+        };
+        if run >= 10 {
             return true;
         }
     }
     false
 }
+
+/// Robots in the default 101 by 103 space, some of which line up into a Christmas tree after a
+/// random number of seconds. The space is the same size however many robots there are, so that
+/// the input still works with the default parameters.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let space = Vec2::new(101, 103);
+    let seconds = rng.range(100..10_000) as isize;
+    let top = Vec2::new(rng.range(20..80) as isize, rng.range(5..70) as isize);
+
+    let tree = (0..12).flat_map(|row| (-row..=row).map(move |x| top + Vec2::new(x, row)));
+    let trunk = (12..15).flat_map(|row| (-1..=1).map(move |x| top + Vec2::new(x, row)));
+    let pictured = tree.chain(trunk).collect_vec();
+    let strays = (pictured.len()..500 * size)
+        .map(|_| Vec2::new(rng.range(0..101) as isize, rng.range(0..103) as isize))
+        .collect_vec();
+
+    let mut robots = pictured
+        .into_iter()
+        .map(|pos| (pos, true))
+        .chain(strays.into_iter().map(|pos| (pos, false)))
+        .map(|(pos, in_tree)| {
+            let vel = Vec2::new(rng.range(-100..101) as isize, rng.range(-100..101) as isize);
+            // Robots in the tree start wherever they have to be to get there in time.
+            let start = if in_tree {
+                (pos - vel * seconds).wrap(space)
+            } else {
+                pos
+            };
+            format!("p={},{} v={},{}\n", start.x, start.y, vel.x, vel.y)
+        })
+        .collect_vec();
+    rng.shuffle(&mut robots);
+    robots.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_outside_the_space() {
+        let size = Vec2::new(5, 7);
+        let error = parse("p=1,2 v=1,1\np=6,3 v=-1,2\n", size).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = parse("p=4,7 v=1,1\n", size).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+        assert!(parse("p=4,6 v=1,1\n", size).is_ok());
+
+        let board = as_board(&[(Vec2::new(6, 3), Vec2::new(0, 0))], size);
+        assert_eq!(board[Vec2::new(1, 3)], '*');
+    }
+}
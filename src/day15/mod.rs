@@ -1,67 +1,90 @@
-use itertools::Itertools;
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{scaled_side, Rng},
+        Dir, Grid, Vec2,
+    },
+};
 
-use crate::utils::input;
+pub struct Day;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 15");
-    let data = input(15, example)?;
-    p1(&data);
-    p2(&data);
+impl Solution for Day {
+    type Input = (Board, Vec<Dir>, Vec2);
 
-    Ok(())
+    const DAY: u16 = 15;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        render(input, frames)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let (mut board, directions, mut robot) = parse(input);
+fn p1((board, directions, robot): &(Board, Vec<Dir>, Vec2)) -> usize {
+    let mut board = board.clone();
+    let mut robot = *robot;
     #[allow(clippy::never_loop)]
-    for dir in directions {
-        if let Some(nb) = push(&board, dir, robot + dir) {
+    for &dir in directions {
+        if let Some(nb) = push(&board, dir, robot + dir.delta()) {
             board = nb;
-            robot = robot + dir;
+            robot = robot + dir.delta();
         }
     }
-    let gps: usize = calc_gps(&board).into_iter().sum();
-    println!("P1: {gps}");
+    calc_gps(&board).into_iter().sum()
 }
 
-fn p2(input: &str) {
-    let (board, directions, robot) = parse(input);
-    let mut board = extend_board(&board);
-    let mut robot = extend_robot(robot);
-    for dir in directions {
-        if let Some(nb) = push(&board, dir, robot + dir) {
+fn p2((board, directions, robot): &(Board, Vec<Dir>, Vec2)) -> usize {
+    let mut board = extend_board(board);
+    let mut robot = extend_robot(*robot);
+    for &dir in directions {
+        if let Some(nb) = push(&board, dir, robot + dir.delta()) {
             board = nb;
-            robot = robot + dir;
+            robot = robot + dir.delta();
         }
     }
-    let gps: usize = calc_gps(&board).into_iter().sum();
-    println!("P2: {gps}");
+    calc_gps(&board).into_iter().sum()
 }
 
-fn push(board: &Board, dir: Vec2, pos: Vec2) -> Option<Board> {
-    let tile = board[pos.y as usize][pos.x as usize];
-    let np = pos + dir;
+fn push(board: &Board, dir: Dir, pos: Vec2) -> Option<Board> {
+    let &tile = board.get(pos)?;
+    let np = pos + dir.delta();
     match tile {
         Tile::Floor => Some(board.clone()),
         Tile::Wall => None,
         Tile::Box => {
             if let Some(mut nb) = push(board, dir, np) {
-                nb[np.y as usize][np.x as usize] = Tile::Box;
-                nb[pos.y as usize][pos.x as usize] = Tile::Floor;
+                nb[np] = Tile::Box;
+                nb[pos] = Tile::Floor;
                 Some(nb)
             } else {
                 None
             }
         }
-        Tile::LBox if dir.x == 0 => {
-            if let Some(nb) = push(board, dir, pos + dir) {
-                if let Some(mut nb) = push(&nb, dir, pos.right() + dir) {
+        Tile::LBox if matches!(dir, Dir::North | Dir::South) => {
+            if let Some(nb) = push(board, dir, np) {
+                if let Some(mut nb) = push(&nb, dir, pos.right() + dir.delta()) {
                     let rbox = pos.right();
-                    let nrbox = rbox + dir;
-                    nb[np.y as usize][np.x as usize] = Tile::LBox;
-                    nb[nrbox.y as usize][nrbox.x as usize] = Tile::RBox;
-                    nb[pos.y as usize][pos.x as usize] = Tile::Floor;
-                    nb[rbox.y as usize][rbox.x as usize] = Tile::Floor;
+                    let nrbox = rbox + dir.delta();
+                    nb[np] = Tile::LBox;
+                    nb[nrbox] = Tile::RBox;
+                    nb[pos] = Tile::Floor;
+                    nb[rbox] = Tile::Floor;
                     Some(nb)
                 } else {
                     None
@@ -71,28 +94,28 @@ fn push(board: &Board, dir: Vec2, pos: Vec2) -> Option<Board> {
             }
         }
 
-        Tile::LBox if dir.x == -1 => {
+        Tile::LBox if dir == Dir::West => {
             let left = pos.left();
             let lbox = pos;
             let rbox = pos.right();
             if let Some(mut nb) = push(board, dir, left) {
-                nb[left.y as usize][left.x as usize] = Tile::LBox;
-                nb[lbox.y as usize][lbox.x as usize] = Tile::RBox;
-                nb[rbox.y as usize][rbox.x as usize] = Tile::Floor;
+                nb[left] = Tile::LBox;
+                nb[lbox] = Tile::RBox;
+                nb[rbox] = Tile::Floor;
                 Some(nb)
             } else {
                 None
             }
         }
 
-        Tile::LBox if dir.x == 1 => {
+        Tile::LBox if dir == Dir::East => {
             let right = pos.right().right();
             let rbox = pos.right();
             let lbox = pos;
             if let Some(mut nb) = push(board, dir, right) {
-                nb[right.y as usize][right.x as usize] = Tile::RBox;
-                nb[rbox.y as usize][rbox.x as usize] = Tile::LBox;
-                nb[lbox.y as usize][lbox.x as usize] = Tile::Floor;
+                nb[right] = Tile::RBox;
+                nb[rbox] = Tile::LBox;
+                nb[lbox] = Tile::Floor;
                 Some(nb)
             } else {
                 None
@@ -104,57 +127,44 @@ fn push(board: &Board, dir: Vec2, pos: Vec2) -> Option<Board> {
     }
 }
 
-fn parse(input: &str) -> (Board, Vec<Vec2>, Vec2) {
-    let mut board = Vec::new();
-    let mut dirs = Vec::new();
-    let mut parse_board = true;
-    let mut robot = Vec2::default();
+fn parse(input: &str) -> Result<(Board, Vec<Dir>, Vec2), ParseError> {
+    let input = Span::new(input);
+    let (map, moves) = input.split_once("\n\n").unwrap_or((input, input.end()));
 
-    for (y, line) in input.lines().enumerate() {
-        if parse_board {
-            if line.is_empty() {
-                parse_board = false;
-                continue;
-            }
-            board.push(
-                line.chars()
-                    .enumerate()
-                    .map(|(x, ch)| match ch {
-                        '@' => {
-                            robot = (x as isize, y as isize).into();
-                            Tile::Floor
-                        }
-                        _ => Tile::from(ch),
-                    })
-                    .collect(),
-            );
-        } else {
-            dirs.extend(line.chars().map(|ch| match ch {
-                '>' => Vec2::from((1isize, 0isize)),
-                '<' => (-1, 0).into(),
-                '^' => (0, -1).into(),
-                'v' => (0, 1).into(),
-                _ => unreachable!(),
-            }));
+    let mut robot = None;
+    let board = Grid::parse(map.as_str(), |pos, ch| match ch {
+        '@' => {
+            robot = Some(pos);
+            Some(Tile::Floor)
         }
+        _ => Tile::try_from(ch).ok(),
+    })?;
+
+    let mut dirs = Vec::new();
+    for (span, ch) in moves.chars() {
+        if ch == '\n' || ch == '\r' {
+            continue;
+        }
+        let dir = Dir::from_arrow(ch).ok_or_else(|| span.error("expected '>', '<', '^' or 'v'"))?;
+        dirs.push(dir);
     }
 
-    (board, dirs, robot)
+    let robot = robot.ok_or_else(|| input.end().error("expected a robot ('@')"))?;
+    Ok((board, dirs, robot))
 }
 
 fn extend_board(board: &Board) -> Board {
-    board
-        .clone()
-        .into_iter()
-        .map(|line| {
-            line.into_iter()
-                .flat_map(|tile| match tile {
-                    Tile::Box => [Tile::LBox, Tile::RBox],
-                    _ => [tile, tile],
-                })
-                .collect_vec()
-        })
-        .collect_vec()
+    Grid::from_fn(board.width() * 2, board.height(), |pos| {
+        let tile = board[Vec2 {
+            x: pos.x / 2,
+            y: pos.y,
+        }];
+        match tile {
+            Tile::Box if pos.x % 2 == 0 => Tile::LBox,
+            Tile::Box => Tile::RBox,
+            _ => tile,
+        }
+    })
 }
 
 fn extend_robot(robot: Vec2) -> Vec2 {
@@ -164,34 +174,47 @@ fn extend_robot(robot: Vec2) -> Vec2 {
     }
 }
 
-#[allow(dead_code)]
-fn debug_board(board: &Board, robot: Vec2) {
-    for (y, line) in board.iter().enumerate() {
-        for (x, tile) in line.iter().enumerate() {
-            if robot == (x as isize, y as isize).into() {
-                print!("@");
-            } else {
-                print!("{tile}");
-            }
+/// One frame per move of the robot around the wide warehouse from part 2.
+fn render(
+    (board, directions, robot): &(Board, Vec<Dir>, Vec2),
+    frames: &mut dyn FrameSink,
+) -> anyhow::Result<()> {
+    let mut board = extend_board(board);
+    let mut robot = extend_robot(*robot);
+    frames.frame(&as_image(&board, robot))?;
+    for &dir in directions {
+        if let Some(nb) = push(&board, dir, robot + dir.delta()) {
+            board = nb;
+            robot = robot + dir.delta();
         }
-        println!()
+        frames.frame(&as_image(&board, robot))?;
     }
+    Ok(())
+}
+
+fn as_image(board: &Board, robot: Vec2) -> Image {
+    let mut image = Image::from_grid(board, |_, tile| match tile {
+        Tile::Floor => Rgb::BLACK,
+        Tile::Wall => Rgb::GREY,
+        Tile::Box | Tile::LBox | Tile::RBox => Rgb::ORANGE,
+    });
+    image.set(robot, Rgb::RED);
+    image
 }
 
 fn calc_gps(board: &Board) -> Vec<usize> {
     let mut gps = Vec::with_capacity(100);
-    for (y, line) in board.iter().enumerate() {
-        for (x, tile) in line.iter().enumerate() {
-            match *tile {
-                Tile::LBox | Tile::Box => gps.push(100 * y + x),
-                _ => {}
-            };
-        }
+    for (pos, tile) in board.iter() {
+        match *tile {
+            Tile::LBox | Tile::Box => gps.push(100 * pos.y as usize + pos.x as usize),
+            _ => {}
+        };
     }
     gps
 }
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Tile {
+pub enum Tile {
     Floor,
     Wall,
     Box,
@@ -199,71 +222,20 @@ enum Tile {
     RBox,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Tile::Floor,
-            '#' => Tile::Wall,
-            'O' => Tile::Box,
-            _ => unreachable!(),
-        }
-    }
-}
-
-type Board = Vec<Vec<Tile>>;
+impl TryFrom<char> for Tile {
+    type Error = char;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-struct Vec2 {
-    x: isize,
-    y: isize,
-}
-
-impl Vec2 {
-    fn right(&self) -> Vec2 {
-        Vec2 {
-            x: self.x + 1,
-            y: self.y,
-        }
-    }
-
-    fn left(&self) -> Vec2 {
-        Vec2 {
-            x: self.x - 1,
-            y: self.y,
-        }
-    }
-}
-
-impl From<(isize, isize)> for Vec2 {
-    fn from(value: (isize, isize)) -> Self {
-        Vec2 {
-            x: value.0,
-            y: value.1,
-        }
-    }
-}
-
-impl std::ops::Add for Vec2 {
-    type Output = Vec2;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Vec2 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '.' => Ok(Tile::Floor),
+            '#' => Ok(Tile::Wall),
+            'O' => Ok(Tile::Box),
+            _ => Err(value),
         }
     }
 }
 
-impl std::ops::Sub for Vec2 {
-    type Output = Vec2;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Vec2 {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
+type Board = Grid<Tile>;
 
 impl std::fmt::Display for Tile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -277,3 +249,30 @@ impl std::fmt::Display for Tile {
         write!(f, "{ch}")
     }
 }
+
+/// A walled warehouse full of boxes and a few walls, and a long list of moves for the robot.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = scaled_side(50, size);
+    let mut map = Grid::from_fn(side, side, |pos| {
+        let edge = side as isize - 1;
+        if pos.x == 0 || pos.y == 0 || pos.x == edge || pos.y == edge || rng.chance(0.05) {
+            '#'
+        } else if rng.chance(0.35) {
+            'O'
+        } else {
+            '.'
+        }
+    });
+    let robot = Vec2::new(side as isize / 2, side as isize / 2);
+    map[robot] = '@';
+
+    let moves = (0..20 * size)
+        .map(|_| {
+            let line = (0..1000)
+                .map(|_| *rng.choose(&['^', 'v', '<', '>']))
+                .collect::<String>();
+            line + "\n"
+        })
+        .collect::<String>();
+    format!("{map}\n{moves}")
+}
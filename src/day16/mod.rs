@@ -1,172 +1,129 @@
-use std::{collections::BinaryHeap, sync::atomic::AtomicUsize};
+use anyhow::bail;
+use rustc_hash::FxHashSet;
 
-use rustc_hash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
 
-use crate::utils::{input, Vec2};
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Params, Solution},
+    utils::{
+        rng::{maze, scaled_side, Rng},
+        search, Dir, Grid, Vec2,
+    },
+};
 
-static PLAYER_ID: AtomicUsize = AtomicUsize::new(0);
+pub struct Day;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 16");
-    let data = input(16, example)?;
-    p1(&data);
-    p2(&data);
+impl Solution for Day {
+    type Input = Board;
 
-    Ok(())
-}
+    const DAY: u16 = 16;
 
-fn p1(input: &str) {
-    let board = parse(input);
-    let (score, count) = board.new_traverse();
-    println!("P1: {score}");
-    println!("P2: {count}");
-}
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
 
-fn p2(_input: &str) {}
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        let (score, _count) = input.traverse()?;
+        Ok(score.into())
+    }
 
-fn parse(input: &str) -> Board {
-    let mut map = Vec::new();
-    let mut pos = Vec2::default();
-    let mut end = Vec2::default();
-    for (y, line) in input.lines().enumerate() {
-        map.push(Vec::new());
-        for (x, ch) in line.chars().enumerate() {
-            let tile = match ch {
-                '.' => Tile::Floor,
-                '#' => Tile::Wall,
-                'S' => {
-                    pos = (x as isize, y as isize).into();
-                    Tile::Floor
-                }
-                'E' => {
-                    end = (x as isize, y as isize).into();
-                    Tile::Floor
-                }
-                _ => unreachable!(),
-            };
-            map[y].push(tile);
-        }
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        let (_score, count) = input.traverse()?;
+        Ok(count.into())
     }
-    Board {
-        map,
-        end,
-        player: Player::new(pos),
+
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        frames.frame(&input.render()?)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
     }
 }
 
+fn parse(input: &str) -> Result<Board, ParseError> {
+    let mut pos = None;
+    let mut end = None;
+    let map = Grid::parse(input, |p, ch| match ch {
+        '.' => Some(Tile::Floor),
+        '#' => Some(Tile::Wall),
+        'S' => {
+            pos = Some(p);
+            Some(Tile::Floor)
+        }
+        'E' => {
+            end = Some(p);
+            Some(Tile::Floor)
+        }
+        _ => None,
+    })?;
+    let input = Span::new(input);
+    let start = pos.ok_or_else(|| input.end().error("expected a start ('S')"))?;
+    let end = end.ok_or_else(|| input.end().error("expected an end ('E')"))?;
+    Ok(Board { map, end, start })
+}
+
 #[derive(Debug, Clone)]
-struct Board {
-    map: Vec<Vec<Tile>>,
+pub struct Board {
+    map: Grid<Tile>,
     end: Vec2,
-    player: Player,
+    start: Vec2,
 }
 
 impl Board {
-    fn new_traverse(&self) -> (usize, usize) {
-        let mut scores = FxHashMap::default();
-        scores.insert((self.player.pos, self.player.dir), 0usize);
-
-        let mut queue = BinaryHeap::from([self.player.clone()]);
-        queue.extend(self.player.cross());
-
-        let mut visited: FxHashMap<usize, FxHashSet<Vec2>> = FxHashMap::default();
-        visited
-            .entry(self.player.id)
-            .or_default()
-            .insert(self.player.pos);
-        let mut happy_player: Vec<Player> = Vec::new();
-
-        while let Some(mut player) = queue.pop() {
-            loop {
-                // Move the player forward
-                let new_players = player.advance();
-
-                visited.entry(player.id).or_default().insert(player.pos);
-
-                let score = scores.entry((player.pos, player.dir)).or_insert(usize::MAX);
-
-                if self.is_wall(player.pos) {
-                    visited.remove(&player.id);
-                    break;
-                }
-
-                if player.score > *score {
-                    visited.remove(&player.id);
-                    break;
-                }
-
-                *score = player.score.min(*score);
-
-                if self.is_end(player.pos) {
-                    happy_player.push(player);
-                    break;
-                }
-
-                // Keep evaluating next players
-                let nv = visited.get(&player.id).unwrap().clone();
-                visited
-                    .entry(new_players[0].id)
-                    .or_insert_with(|| nv.clone());
-                visited.entry(new_players[1].id).or_insert_with(|| nv);
-                queue.extend(new_players);
-            }
-        }
-
-        let lowest_score = scores
-            .into_iter()
-            .filter_map(|((p, _d), v)| if p == self.end { Some(v) } else { None })
-            .min()
-            .unwrap();
-        let mut success_set = happy_player
+    /// The lowest score to reach the end, and how many tiles are on any path with that score.
+    fn traverse(&self) -> anyhow::Result<(usize, usize)> {
+        let (lowest_score, tiles) = self.best_tiles()?;
+        Ok((lowest_score, tiles.len()))
+    }
+
+    /// The lowest score to reach the end, and every tile on any path with that score.
+    fn best_tiles(&self) -> anyhow::Result<(usize, FxHashSet<Vec2>)> {
+        let shortest = search::all_shortest_paths((self.start, Dir::East), |&(pos, dir)| {
+            let ahead = pos + dir.delta();
+            let forward = (!self.is_wall(ahead)).then_some(((ahead, dir), 1));
+            let turns = [dir.turn_left(), dir.turn_right()]
+                .into_iter()
+                .map(move |other| ((pos, other), 1000));
+            forward.into_iter().chain(turns)
+        });
+
+        let ends = Dir::all()
+            .filter_map(|dir| Some(((self.end, dir), shortest.cost(&(self.end, dir))?)))
+            .collect::<Vec<_>>();
+        let Some(lowest_score) = ends.iter().map(|&(_, score)| score).min() else {
+            bail!("the end can't be reached");
+        };
+        let tiles = shortest
+            .nodes_on_paths(
+                ends.into_iter()
+                    .filter(|&(_, score)| score == lowest_score)
+                    .map(|(end, _)| end),
+            )
             .into_iter()
-            .filter_map(|player| {
-                if player.score <= lowest_score {
-                    Some(player.id)
-                } else {
-                    None
-                }
-            })
-            .flat_map(|player_id| &visited[&player_id])
-            .copied()
+            .map(|(pos, _)| pos)
             .collect::<FxHashSet<_>>();
-        success_set.insert(self.player.pos);
-        (lowest_score, success_set.len())
-    }
-
-    #[allow(dead_code)]
-    fn print_debug(&self, markers: Option<FxHashSet<Vec2>>) {
-        let markers = markers.unwrap_or_default();
-        for (y, line) in self.map.iter().enumerate() {
-            print!("{y:>3}: ");
-            for (x, tile) in line.iter().enumerate() {
-                let pos: Vec2 = (x as isize, y as isize).into();
-                if markers.contains(&pos) {
-                    print!("O");
-                } else if pos == self.player.pos {
-                    print!("S");
-                } else if pos == self.end {
-                    print!("E");
-                } else {
-                    print!("{tile}");
-                }
-            }
-            println!()
-        }
+        Ok((lowest_score, tiles))
     }
 
-    fn get(&self, pos: Vec2) -> Option<Tile> {
-        self.map
-            .get(pos.y as usize)
-            .and_then(|line| line.get(pos.x as usize))
-            .copied()
+    /// The maze with every tile on a best path marked.
+    fn render(&self) -> anyhow::Result<Image> {
+        let (_, tiles) = self.best_tiles()?;
+        let mut image = Image::from_grid(&self.map, |pos, tile| match tile {
+            Tile::Wall => Rgb::GREY,
+            Tile::Floor if tiles.contains(&pos) => Rgb::YELLOW,
+            Tile::Floor => Rgb::BLACK,
+        });
+        image.set(self.start, Rgb::GREEN);
+        image.set(self.end, Rgb::RED);
+        Ok(image)
     }
 
+    /// Anything off the map counts as a wall too.
     fn is_wall(&self, pos: Vec2) -> bool {
-        self.get(pos) == Some(Tile::Wall)
-    }
-
-    fn is_end(&self, pos: Vec2) -> bool {
-        pos == self.end
+        self.map.get(pos) != Some(&Tile::Floor)
     }
 }
 
@@ -186,142 +143,25 @@ impl std::fmt::Display for Tile {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum Dir {
-    North,
-    South,
-    East,
-    West,
-}
-
-impl Dir {
-    fn to(&self, other: Dir) -> usize {
-        match self {
-            Dir::North => match other {
-                Dir::North => 0,
-                Dir::South => 2000,
-                Dir::East => 1000,
-                Dir::West => 1000,
-            },
-            Dir::South => match other {
-                Dir::North => 2000,
-                Dir::South => 0,
-                Dir::East => 1000,
-                Dir::West => 1000,
-            },
-            Dir::East => match other {
-                Dir::North => 1000,
-                Dir::South => 1000,
-                Dir::East => 0,
-                Dir::West => 2000,
-            },
-            Dir::West => match other {
-                Dir::North => 1000,
-                Dir::South => 1000,
-                Dir::East => 2000,
-                Dir::West => 0,
-            },
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct Player {
-    id: usize,
-    pos: Vec2,
-    dir: Dir,
-    score: usize,
-}
-
-impl Player {
-    fn new(pos: Vec2) -> Player {
-        Player {
-            id: PLAYER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
-            pos,
-            dir: Dir::East,
-            score: 0,
-        }
-    }
-
-    fn advance(&mut self) -> [Player; 2] {
-        self.go(self.dir);
-
-        self.cross()
-    }
-
-    fn east_west_cross(&self) -> [Player; 2] {
-        let east = {
-            let mut east = self.clone();
-            east.turn(Dir::East);
-            east
-        };
-        let west = {
-            let mut west = self.clone();
-            west.turn(Dir::West);
-            west
-        };
-
-        [east, west]
-    }
-
-    fn north_south_cross(&self) -> [Player; 2] {
-        let north = {
-            let mut north = self.clone();
-            north.turn(Dir::North);
-            north
-        };
-        let south = {
-            let mut south = self.clone();
-            south.turn(Dir::South);
-            south
-        };
-
-        [north, south]
-    }
-
-    fn turn(&mut self, dir: Dir) {
-        self.score += self.dir.to(dir);
-        self.dir = dir;
-    }
-
-    fn go(&mut self, dir: Dir) {
-        self.turn(dir);
-        self.pos = match dir {
-            Dir::North => self.pos.up(),
-            Dir::South => self.pos.down(),
-            Dir::East => self.pos.right(),
-            Dir::West => self.pos.left(),
-        };
-        self.score += 1;
-    }
-
-    fn cross(&self) -> [Player; 2] {
-        match self.dir {
-            Dir::North | Dir::South => self.east_west_cross(),
-            Dir::East | Dir::West => self.north_south_cross(),
-        }
-    }
-}
-
-impl PartialOrd for Player {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Player {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.score.cmp(&other.score)
-    }
-}
-
-impl Clone for Player {
-    fn clone(&self) -> Self {
-        Self {
-            id: PLAYER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
-            pos: self.pos,
-            dir: self.dir,
-            score: self.score,
+/// A maze from the bottom left corner to the top right one, with some walls knocked through so
+/// that there are several ways around.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut walls = maze(rng, scaled_side(70, size));
+    let side = walls.width() as isize;
+    for pos in walls.positions().collect_vec() {
+        let inner = (1..side - 1).contains(&pos.x) && (1..side - 1).contains(&pos.y);
+        // Walls between two passages sit on an odd row and even column, or the other way around.
+        if inner && (pos.x + pos.y) % 2 == 1 && rng.chance(0.1) {
+            walls[pos] = false;
         }
     }
+    let start = Vec2::new(1, side - 2);
+    let end = Vec2::new(side - 2, 1);
+    Grid::from_fn(walls.width(), walls.height(), |pos| match pos {
+        _ if pos == start => 'S',
+        _ if pos == end => 'E',
+        _ if walls[pos] => '#',
+        _ => '.',
+    })
+    .to_string()
 }
@@ -1,53 +1,98 @@
 use std::collections::VecDeque;
 
-use itertools::Itertools;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-use crate::utils::input;
+pub struct Day;
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 17");
-    let data = input(17, example)?;
-    p1(&data);
-    p2(&data);
+impl Solution for Day {
+    type Input = Computer;
 
-    Ok(())
+    const DAY: u16 = 17;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Computer::parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p1(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("The program doesn't halt"))
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p2(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("No value of A prints the program"))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
+}
+
+/// Instructions a program can run before it's taken to never halt. The real programs halt within a
+/// couple of hundred.
+const MAX_STEPS: usize = 10_000;
+
+/// Values of A part 2 tries before giving up. The real programs need a few hundred.
+const MAX_CANDIDATES: usize = 10_000;
+
+fn p1(computer: &Computer) -> Option<String> {
+    let mut computer = computer.clone();
+    computer.execute().then(|| computer.output())
 }
 
-fn p1(input: &str) {
-    let mut computer = Computer::parse(input);
-    computer.execute();
-    println!("P1: {}", computer.output());
+fn p2(computer: &Computer) -> Option<i64> {
+    lowest_a_printing(computer, &computer.ins)
 }
 
-fn p2(input: &str) {
-    let mut computer = Computer::parse(input);
+/// The lowest value of A that makes the program print `target`. The programs shift A three bits
+/// right every loop and print something of its lowest bits, so the last number printed only
+/// depends on the highest octal digit of A. This works backwards, trying each next octal digit
+/// after any prefix of A that prints the end of the target.
+fn lowest_a_printing(computer: &Computer, target: &[i64]) -> Option<i64> {
+    let mut computer = computer.clone();
     let mut candidates = VecDeque::from([0i64]);
-    let mut min_a = i64::MAX;
+    let mut min_a = None;
+    let mut tried = 0;
     'outer: while let Some(candidate) = candidates.pop_front() {
+        // Candidates come out in increasing order, so nothing after this can beat the best yet.
+        if min_a.is_some_and(|min| candidate.saturating_mul(8) > min) {
+            break;
+        }
         for i in 0i64..8 {
-            let target = (candidate << 3) + i;
-            let result = computer.solve(target).to_vec();
+            let a = candidate.checked_mul(8)? + i;
+            tried += 1;
+            if tried > MAX_CANDIDATES {
+                return None;
+            }
+            let result = computer.solve(a)?;
 
-            if computer.out.len() > computer.ins.len() {
+            if result.len() > target.len() {
                 break 'outer;
             }
 
-            if computer.ins == computer.out {
-                min_a = min_a.min(target);
+            if result == target {
+                min_a = Some(min_a.map_or(a, |min: i64| min.min(a)));
             }
 
-            if computer.ins.ends_with(&result) {
-                candidates.push_back(target);
+            // Zero is where the search started, so queueing it again would go round forever when
+            // nothing prints the target.
+            if a != 0 && target.ends_with(result) {
+                candidates.push_back(a);
             }
         }
     }
 
-    // let ra = final_candidates.iter().copied().min().unwrap();
-    println!("P2: {min_a}");
+    min_a
 }
 
-#[derive(Debug, Default)]
-struct Computer {
+#[derive(Debug, Default, Clone)]
+pub struct Computer {
     init: (i64, i64, i64),
     ra: i64,
     rb: i64,
@@ -58,11 +103,11 @@ struct Computer {
 }
 
 impl Computer {
-    fn solve(&mut self, a: i64) -> &[i64] {
+    /// What the program prints starting with `a` in register A, if it halts.
+    fn solve(&mut self, a: i64) -> Option<&[i64]> {
         self.reset();
         self.ra = a;
-        self.execute();
-        &self.out
+        self.execute().then_some(&self.out)
     }
 
     fn reset(&mut self) {
@@ -74,8 +119,13 @@ impl Computer {
         self.ip = 0;
     }
 
-    fn execute(&mut self) {
-        while self.ip < self.ins.len() {
+    /// Runs the program until it halts, or gives up and returns false after [`MAX_STEPS`].
+    fn execute(&mut self) -> bool {
+        for _ in 0..MAX_STEPS {
+            // Reading an opcode, or its operand, from past the end halts the program.
+            if self.ip + 1 >= self.ins.len() {
+                return true;
+            }
             let mut jumped = false;
 
             let ins = self.ins[self.ip];
@@ -97,6 +147,7 @@ impl Computer {
                 self.ip += 2;
             }
         }
+        false
     }
 
     fn output(&self) -> String {
@@ -104,7 +155,16 @@ impl Computer {
     }
 
     fn adv(&mut self, op: i64) {
-        self.ra /= 2i64.pow(self.combo(op) as u32);
+        self.ra = self.dv(op);
+    }
+
+    /// A divided by two to the power of the combo operand. The registers are never negative, so
+    /// this is a shift, and shifting by 64 or more leaves nothing.
+    fn dv(&self, op: i64) -> i64 {
+        u32::try_from(self.combo(op))
+            .ok()
+            .and_then(|shift| self.ra.checked_shr(shift))
+            .unwrap_or(0)
     }
 
     fn bxl(&mut self, op: i64) {
@@ -132,38 +192,51 @@ impl Computer {
     }
 
     fn bdv(&mut self, op: i64) {
-        self.rb = self.ra / 2i64.pow(self.combo(op) as u32);
+        self.rb = self.dv(op);
     }
 
     fn cdv(&mut self, op: i64) {
-        self.rc = self.ra / 2i64.pow(self.combo(op) as u32);
+        self.rc = self.dv(op);
     }
 
-    fn parse(input: &str) -> Self {
-        let mut comp = input.lines().fold(Computer::default(), |mut comp, line| {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut comp = Computer::default();
+        for line in Span::new(input).lines() {
             if line.is_empty() {
-                return comp;
+                continue;
             }
-            let (label, value) = line.split_once(':').unwrap();
-            match label {
-                "Register A" => comp.ra = value.trim().parse().unwrap(),
-                "Register B" => comp.rb = value.trim().parse().unwrap(),
-                "Register C" => comp.rc = value.trim().parse().unwrap(),
+            let (label, value) = line.split_once(":")?;
+            match label.as_str() {
+                "Register A" => comp.ra = parse_register(value.trim())?,
+                "Register B" => comp.rb = parse_register(value.trim())?,
+                "Register C" => comp.rc = parse_register(value.trim())?,
                 "Program" => {
+                    let value = value.trim();
                     comp.ins = value
-                        .trim()
-                        .split(',')
-                        .map(|n| n.parse().unwrap())
-                        .collect_vec()
+                        .split(",")
+                        .map(|n| match n.parse()? {
+                            op @ 0..=7 => Ok(op),
+                            _ => Err(n.error("expected a 3-bit number")),
+                        })
+                        .collect::<Result<_, _>>()?;
+                    if !comp.ins.len().is_multiple_of(2) {
+                        return Err(value.error("expected an operand after every instruction"));
+                    }
+                    // adv, bst, out, bdv and cdv take a combo operand, where 7 is reserved.
+                    let operands = value.split(",").skip(1).step_by(2);
+                    for (ins, operand) in comp.ins.chunks(2).zip(operands) {
+                        if matches!(ins, [0 | 2 | 5 | 6 | 7, 7]) {
+                            return Err(operand.error("expected a combo operand from 0 to 6"));
+                        }
+                    }
                 }
-                _ => unreachable!(),
+                _ => return Err(label.error("expected a register or the program")),
             }
-            comp
-        });
+        }
 
         comp.init = (comp.ra, comp.rb, comp.rc);
 
-        comp
+        Ok(comp)
     }
 
     fn combo(&self, op: i64) -> i64 {
@@ -176,3 +249,119 @@ impl Computer {
         }
     }
 }
+
+fn parse_register(value: Span) -> Result<i64, ParseError> {
+    match value.parse::<i64>()? {
+        n @ 0.. => Ok(n),
+        _ => Err(value.error("expected a register that isn't negative")),
+    }
+}
+
+/// A program shaped like the real ones: each loop outputs a few bits of A mixed up with two
+/// constants, then shifts A three bits right until it runs out. The constants are ones for which
+/// some value of A makes the program print itself, as part 2 needs. The program is always the same
+/// length, so the size is ignored.
+fn generate(rng: &mut Rng, _size: usize) -> String {
+    const QUINE_CONSTANTS: &[(u8, u8)] = &[
+        (0, 5),
+        (2, 7),
+        (3, 0),
+        (3, 4),
+        (3, 5),
+        (5, 6),
+        (6, 7),
+        (7, 7),
+    ];
+    let &(k1, k2) = rng.choose(QUINE_CONSTANTS);
+    format!(
+        "Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: 2,4,1,{k1},7,5,0,3,1,{k2},4,1,5,5,3,0\n",
+        rng.range(1 << 45..1 << 48),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// [`lowest_a_printing`] done the slow way, by running every A in turn. A program of the
+    /// generated shape prints one number per octal digit of A, so nothing from `8^len` up can print
+    /// a target `len` numbers long.
+    fn brute_force_lowest_a(computer: &Computer, target: &[i64]) -> Option<i64> {
+        let mut computer = computer.clone();
+        (0..8i64.pow(target.len() as u32)).find(|&a| computer.solve(a) == Some(target))
+    }
+
+    #[test]
+    fn test_lowest_a_matches_brute_force() {
+        let mut unprintable = 0;
+        for seed in 0..100 {
+            let mut rng = Rng::new(seed);
+            let mut computer = Computer::parse(&generate(&mut rng, 1)).unwrap();
+            for len in 1..=5 {
+                // Half the targets are printed by some A, the other half are probably not.
+                let target = if rng.chance(0.5) {
+                    computer
+                        .solve(rng.range(0..8i64.pow(len)))
+                        .unwrap()
+                        .to_vec()
+                } else {
+                    (0..len).map(|_| rng.range(0..8)).collect()
+                };
+                let expected = brute_force_lowest_a(&computer, &target);
+                assert_eq!(
+                    lowest_a_printing(&computer, &target),
+                    expected,
+                    "seed {seed}, program {:?}, target {target:?}",
+                    computer.ins,
+                );
+                unprintable += expected.is_none() as usize;
+            }
+        }
+        assert!(unprintable > 0);
+    }
+
+    #[test]
+    fn test_no_a_prints_the_program() {
+        // This prints the octal digits of A from the lowest up, and the highest can't be a zero.
+        let computer = Computer::parse("Register A: 0\n\nProgram: 5,4,0,3,3,0\n").unwrap();
+        assert_eq!(brute_force_lowest_a(&computer, &computer.ins), None);
+        assert_eq!(p2(&computer), None);
+        assert!(Day.part2(&computer).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "Register A: -1\n\nProgram: 0,3,5,4,3,0\n",
+            "Register A: 1\n\nProgram: 0,7,5,4,3,0\n",
+            "Register A: 1\n\nProgram: 1,7,5,7,3,0\n",
+        ] {
+            assert!(Computer::parse(input).is_err(), "{input:?}");
+        }
+        assert!(Computer::parse("Register A: 1\n\nProgram: 1,7,3,7\n").is_ok());
+    }
+
+    #[test]
+    fn test_programs_that_misbehave() {
+        // Jumps back to the start forever, since nothing changes A.
+        let computer = Computer::parse("Register A: 1\n\nProgram: 5,4,3,0\n").unwrap();
+        assert_eq!(p1(&computer), None);
+        assert!(Day.part1(&computer).is_err());
+
+        // Jumps to the last number, which is an opcode without an operand, so it halts.
+        let computer = Computer::parse("Register A: 1\n\nProgram: 3,3,5,4\n").unwrap();
+        assert_eq!(p1(&computer), Some(String::new()));
+
+        // Shifts A further than it has bits.
+        let computer = Computer::parse("Register A: 9\n\nProgram: 6,4,5,5\n").unwrap();
+        assert_eq!(p1(&computer), Some("0".to_string()));
+
+        // No program at all is printed by A = 0.
+        assert_eq!(p2(&Computer::parse("").unwrap()), Some(0));
+
+        // Every A prints a zero for each of its octal digits, so every candidate looks promising
+        // and the search gives up long before reaching the 20-digit answer.
+        let computer = Computer::parse("Program: 0,3,5,0,3,0\n").unwrap();
+        assert_eq!(lowest_a_printing(&computer, &[0; 20]), None);
+    }
+}
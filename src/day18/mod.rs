@@ -1,132 +1,206 @@
-use std::collections::{HashMap, HashSet};
-
 use itertools::Itertools;
 
-use crate::utils::{input, Dir, Vec2};
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Param, Params, Solution},
+    utils::{rng::Rng, search, Grid, Vec2},
+};
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Memory;
+
+    const DAY: u16 = 18;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "size",
+            default: "70",
+            help: "The largest coordinate in the memory space",
+        },
+        Param {
+            name: "ticks",
+            default: "1024",
+            help: "How many bytes have fallen in part 1",
+        },
+    ];
+
+    fn parse(&self, input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Memory::parse(
+            input,
+            params.get("size")?,
+            params.get("ticks")?,
+        )?)
+    }
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 18");
-    let data = input(18, example)?;
-    p1(&data);
-    p2(&data);
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p1(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("No path to the exit"))
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p2(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("No byte cuts off the exit"))
+    }
 
-    Ok(())
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        input.render(frames)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let memory = Memory::parse(input);
-    let ticks = if memory.size == 70 { 1024 } else { 12 };
-    let min_cost = memory.find_path(ticks);
-    println!("P1: {}", min_cost.unwrap());
+fn p1(memory: &Memory) -> Option<usize> {
+    memory.find_path(memory.ticks)
 }
 
-fn p2(input: &str) {
-    let memory = Memory::parse(input);
-    let tick_idx = memory
-        .bytes
-        .iter()
-        .copied()
-        .enumerate()
+/// The first byte to fall after the last moment the exit can still be reached.
+fn p2(memory: &Memory) -> Option<String> {
+    let ticks = (0..=memory.bytes.len())
         .rev()
-        .find_map(|(tick_idx, _node)| {
-            let ticks = tick_idx + 1;
-            memory.find_path(ticks).and(Some(tick_idx + 1))
-        })
-        .unwrap();
-    let node = memory.bytes[tick_idx];
-    let tick = format!("{},{}", node.x, node.y);
-    println!("P2: {tick}");
+        .find(|&ticks| memory.find_path(ticks).is_some())?;
+    let node = memory.bytes.get(ticks)?;
+    Some(format!("{},{}", node.x, node.y))
 }
 
 #[derive(Debug)]
-struct Memory {
+pub struct Memory {
     size: isize,
+    ticks: usize,
     bytes: Vec<Vec2>,
 }
 
 impl Memory {
     fn find_path(&self, ticks: usize) -> Option<usize> {
-        let bad_bytes: HashSet<Vec2> = self.tick(ticks).iter().copied().collect();
-        let mut queue = Vec::from([(0usize, Vec2 { x: 0, y: 0 }, Dir::North)]);
+        let corrupted = self.corrupted(ticks);
         let end = Vec2 {
             x: self.size,
             y: self.size,
         };
-        let mut scores = HashMap::new();
-
-        while let Some((cost, coord, dir)) = queue.pop() {
-            let score = scores.entry((coord, dir)).or_insert(usize::MAX);
-
-            *score = cost.min(*score);
-
-            if coord == end {
-                continue;
-            }
-
-            for (next_node, next_dir) in [
-                (coord.right(), Dir::East),
-                (coord.down(), Dir::South),
-                (coord.left(), Dir::West),
-                (coord.up(), Dir::North),
-            ] {
-                if next_node.x < 0
-                    || next_node.y < 0
-                    || next_node.x > self.size
-                    || next_node.y > self.size
-                    || bad_bytes.contains(&next_node)
-                    || cost + 1
-                        >= scores
-                            .get(&(next_node, next_dir))
-                            .copied()
-                            .unwrap_or(usize::MAX)
-                {
-                    continue;
-                }
-
-                queue.push((cost + 1, next_node, next_dir));
-            }
+        if corrupted[Vec2 { x: 0, y: 0 }] {
+            return None;
         }
-
-        scores
-            .iter()
-            .filter_map(|((n, _), v)| if *n == end { Some(*v) } else { None })
-            .min()
-        // *scores.get(&end).unwrap()
+        let steps = search::bfs(Vec2 { x: 0, y: 0 }, |&pos| {
+            corrupted
+                .neighbors(pos)
+                .filter(|&next| !corrupted[next])
+                .collect::<Vec<_>>()
+        });
+        steps.get(&end).copied()
     }
 
     fn tick(&self, ticks: usize) -> &[Vec2] {
-        &self.bytes[..ticks]
+        &self.bytes[..ticks.min(self.bytes.len())]
+    }
+
+    /// The memory space after `ticks` bytes have fallen, where `true` marks a corrupted byte.
+    fn corrupted(&self, ticks: usize) -> Grid<bool> {
+        let side = self.size as usize + 1;
+        let mut grid = Grid::filled(side, side, false);
+        for &byte in self.tick(ticks) {
+            grid[byte] = true;
+        }
+        grid
     }
 
-    fn parse(input: &str) -> Self {
+    fn parse(input: &str, size: usize, ticks: usize) -> Result<Self, ParseError> {
+        let input = Span::new(input);
         let bytes = input
             .lines()
             .map(|line| {
-                let (x, y) = line.split_once(',').unwrap();
-                let x = x.parse().unwrap();
-                let y = y.parse().unwrap();
-                Vec2 { x, y }
+                let (x, y) = line.split_once(",")?;
+                let coord = |span: Span| match span.parse::<usize>()? {
+                    c if c > size => Err(span.error(format!("expected a coordinate up to {size}"))),
+                    c => Ok(c as isize),
+                };
+                Ok(Vec2 {
+                    x: coord(x)?,
+                    y: coord(y)?,
+                })
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let size = bytes.iter().map(|v| v.x.max(v.y)).max().unwrap();
+        if bytes.is_empty() {
+            return Err(input.end().error("expected at least one byte"));
+        }
 
-        Memory { size, bytes }
+        Ok(Memory {
+            size: size as isize,
+            ticks,
+            bytes,
+        })
     }
 
-    #[allow(dead_code)]
-    fn debug_print(&self, ticks: usize) {
-        let c: HashSet<Vec2> = self.tick(ticks).iter().copied().collect();
-        for y in 0..=self.size {
-            for x in 0..=self.size {
-                let v = Vec2 { x, y };
-                if c.contains(&v) {
-                    print!("#");
-                } else {
-                    print!(".");
+    /// One frame per fallen byte, with everything still reachable from the start shaded by its
+    /// distance, until the exit is cut off.
+    fn render(&self, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        let end = Vec2 {
+            x: self.size,
+            y: self.size,
+        };
+        for ticks in 0..=self.bytes.len() {
+            let corrupted = self.corrupted(ticks);
+            let steps = search::bfs(Vec2 { x: 0, y: 0 }, |&pos| {
+                corrupted
+                    .neighbors(pos)
+                    .filter(|&next| !corrupted[next])
+                    .collect::<Vec<_>>()
+            });
+            let furthest = steps.values().max().copied().unwrap_or(0).max(1);
+            let mut image = Image::from_grid(&corrupted, |pos, &corrupt| {
+                match (corrupt, steps.get(&pos)) {
+                    (true, _) => Rgb::GREY,
+                    (false, Some(&n)) => Rgb::BLUE.lerp(Rgb::GREEN, n as f64 / furthest as f64),
+                    (false, None) => Rgb::BLACK,
                 }
+            });
+            let blocked = !steps.contains_key(&end);
+            if let Some(&byte) = self.tick(ticks).last().filter(|_| blocked) {
+                image.set(byte, Rgb::RED);
+            }
+            frames.frame(&image)?;
+            if blocked {
+                break;
             }
-            println!();
         }
+        Ok(())
+    }
+}
+
+/// Bytes falling on every spot of the default 71 by 71 memory space except the corners, in a
+/// random order, so that the way out is always cut off eventually. Bigger sizes only fill more of
+/// the space, up to all of it.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let side = 71;
+    let mut bytes = (0..side)
+        .cartesian_product(0..side)
+        .filter(|&pos| pos != (0, 0) && pos != (side - 1, side - 1))
+        .collect_vec();
+    rng.shuffle(&mut bytes);
+    bytes.truncate(3450 * size);
+    bytes.iter().map(|(x, y)| format!("{x},{y}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p2_without_a_blocking_byte() {
+        // Nothing ever cuts off the exit.
+        let memory = Memory::parse("0,1\n", 70, 1024).unwrap();
+        assert_eq!(p2(&memory), None);
+        assert!(Day.part2(&memory).is_err());
+
+        // The very first byte lands on the start.
+        let memory = Memory::parse("0,0\n1,1\n", 6, 12).unwrap();
+        assert_eq!(p1(&memory), None);
+        assert_eq!(p2(&memory), Some("0,0".to_string()));
     }
 }
@@ -2,37 +2,50 @@ use std::{cmp::Reverse, collections::HashMap};
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 19");
-    let data = input(19, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+impl Solution for Day {
+    type Input = (Vec<String>, Vec<String>);
 
-fn p1(input: &str) {
-    let (towels, designs) = parse(input);
+    const DAY: u16 = 19;
 
-    let mut cache = HashMap::new();
-    let matches = designs
-        .into_iter()
-        .filter(|d| count_combinations(d, &towels, &mut cache) > 0)
-        .count();
-    println!("P1: {matches}");
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p2(input: &str) {
-    let (towels, designs) = parse(input);
+fn p1((towels, designs): &(Vec<String>, Vec<String>)) -> usize {
+    let mut cache = HashMap::new();
+    designs
+        .iter()
+        .filter(|d| count_combinations(d, towels, &mut cache) > 0)
+        .count()
+}
 
+fn p2((towels, designs): &(Vec<String>, Vec<String>)) -> usize {
     let mut cache = HashMap::new();
-    let sum: usize = designs
-        .into_iter()
-        .map(|design| count_combinations(&design, &towels, &mut cache))
-        .sum();
-    println!("P2: {sum}");
+    designs
+        .iter()
+        .map(|design| count_combinations(design, towels, &mut cache))
+        .sum()
 }
 
 fn count_combinations(
@@ -60,16 +73,59 @@ fn count_combinations(
     count
 }
 
-fn parse(input: &str) -> (Vec<String>, Vec<String>) {
+fn parse(input: &str) -> Result<(Vec<String>, Vec<String>), ParseError> {
+    let input = Span::new(input);
     let mut lines = input.lines();
     let mut towels = lines
         .next()
-        .unwrap()
+        .ok_or_else(|| input.end().error("expected a list of towels"))?
         .split(", ")
-        .map(ToString::to_string)
-        .collect_vec();
+        .map(|towel| {
+            if towel.is_empty() {
+                Err(towel.error("expected a towel"))
+            } else {
+                Ok(towel.as_str().to_string())
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
     towels.sort_by_key(|towel| Reverse(towel.len()));
-    lines.next().unwrap();
-    let designs = lines.map(ToString::to_string).collect_vec();
-    (towels, designs)
+    match lines.next() {
+        Some(line) if line.is_empty() => {}
+        Some(line) => return Err(line.error("expected an empty line")),
+        None => return Err(input.end().error("expected an empty line")),
+    }
+    let designs = lines.map(|line| line.as_str().to_string()).collect_vec();
+    Ok((towels, designs))
+}
+
+/// Towels of up to eight stripes, leaving out the plain towel of one colour, and designs that are
+/// mostly made of towels with some random ones that might not be.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    const COLORS: &[char] = &['w', 'u', 'b', 'r', 'g'];
+    let missing = rng.choose(COLORS).to_string();
+    let mut towels = (0..450 * size)
+        .map(|_| {
+            (0..rng.range(1..9))
+                .map(|_| *rng.choose(COLORS))
+                .collect::<String>()
+        })
+        .filter(|towel| *towel != missing)
+        .unique()
+        .collect_vec();
+    rng.shuffle(&mut towels);
+
+    let designs = (0..400 * size)
+        .map(|_| {
+            let mut design = String::new();
+            while design.len() < rng.range(20..60) as usize {
+                if rng.chance(0.9) {
+                    design.push_str(rng.choose(&towels).as_str());
+                } else {
+                    design.push(*rng.choose(COLORS));
+                }
+            }
+            design + "\n"
+        })
+        .collect::<String>();
+    format!("{}\n\n{designs}", towels.join(", "))
 }
@@ -1,172 +1,219 @@
-use core::panic;
 use std::collections::{HashMap, HashSet};
 
-use crate::utils::{input, Vec2};
+use crate::{
+    parse::{ParseError, Span},
+    render::{FrameSink, Image, Rgb},
+    solution::{Answer, Param, Params, Solution},
+    utils::{
+        parallel,
+        rng::{maze, scaled_side, Rng},
+        search, Grid, Vec2,
+    },
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 20");
-    let data = input(20, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+impl Solution for Day {
+    type Input = Track;
+
+    const DAY: u16 = 20;
 
-fn p1(input: &str) {
-    let track = Track::parse(input);
+    const PARAMS: &'static [Param] = &[Param {
+        name: "threshold",
+        default: "100",
+        help: "The fewest picoseconds a cheat has to save to be counted",
+    }];
 
-    let cheats = track
+    fn parse(&self, input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Track::parse(input, params.get("threshold")?)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn render(&self, input: &Self::Input, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        frames.frame(&input.render())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
+}
+
+fn p1(track: &Track) -> usize {
+    track
         .count_cheats(2)
         .into_iter()
-        .filter_map(|(saving, count)| if saving >= 100 { Some(count) } else { None })
-        .sum::<usize>();
-    println!("P1: {cheats}");
+        .filter_map(|(saving, count)| {
+            if saving >= track.threshold {
+                Some(count)
+            } else {
+                None
+            }
+        })
+        .sum::<usize>()
 }
 
-fn p2(input: &str) {
-    let track = Track::parse(input);
-
-    let cheats = track
+fn p2(track: &Track) -> usize {
+    track
         .count_cheats(20)
         .into_iter()
-        .filter_map(|(saving, count)| if saving >= 100 { Some(count) } else { None })
-        .sum::<usize>();
-    println!("P2: {cheats}");
+        .filter_map(|(saving, count)| {
+            if saving >= track.threshold {
+                Some(count)
+            } else {
+                None
+            }
+        })
+        .sum::<usize>()
 }
 
 #[derive(Debug)]
-struct Track {
-    #[allow(dead_code)]
-    size: Vec2,
+pub struct Track {
     start: Vec2,
-    #[allow(dead_code)]
     end: Vec2,
     track: HashMap<Vec2, usize>,
-    #[allow(dead_code)]
-    walls: HashSet<Vec2>,
+    walls: Grid<bool>,
+    threshold: usize,
 }
 
 impl Track {
     fn count_cheats(&self, cheat_range: usize) -> HashMap<usize, usize> {
-        let mut cheats = HashMap::new();
-
-        for (tile, tile_cost) in &self.track {
-            let savings = self
-                .track
-                .iter()
-                .filter_map(|(other_tile, other_cost)| {
-                    let md = tile.manhattan_distance(other_tile);
-                    if md <= cheat_range {
-                        Some((other_tile, other_cost, md))
-                    } else {
-                        None
-                    }
-                })
-                .filter_map(|(_other_tile, other_cost, md)| {
-                    let savings = other_cost.saturating_sub(*tile_cost).saturating_sub(md);
-                    if savings > 0 {
-                        Some(savings)
-                    } else {
-                        None
-                    }
-                });
-
-            for saving in savings {
-                *cheats.entry(saving).or_default() += 1;
-            }
-        }
-
-        cheats
+        let tiles = self.track.iter().collect::<Vec<_>>();
+        parallel::fold(
+            &tiles,
+            HashMap::new,
+            |mut cheats, (tile, tile_cost)| {
+                let savings = tiles
+                    .iter()
+                    .filter_map(|(other_tile, other_cost)| {
+                        let md = tile.manhattan_distance(other_tile);
+                        if md <= cheat_range {
+                            Some((other_tile, other_cost, md))
+                        } else {
+                            None
+                        }
+                    })
+                    .filter_map(|(_other_tile, other_cost, md)| {
+                        let savings = other_cost.saturating_sub(**tile_cost).saturating_sub(md);
+                        if savings > 0 {
+                            Some(savings)
+                        } else {
+                            None
+                        }
+                    });
+
+                for saving in savings {
+                    *cheats.entry(saving).or_default() += 1;
+                }
+                cheats
+            },
+            |mut cheats, other| {
+                for (saving, count) in other {
+                    *cheats.entry(saving).or_default() += count;
+                }
+                cheats
+            },
+        )
     }
 
     fn assign_costs(&mut self) {
-        let mut visited = HashSet::from([self.start]);
-        let mut queue = Vec::from([(0usize, self.start)]);
-
-        while let Some((cost, coord)) = queue.pop() {
-            for coord in [coord.up(), coord.down(), coord.left(), coord.right()] {
-                if !self.track.contains_key(&coord) {
-                    continue;
-                }
-
-                if !visited.insert(coord) {
-                    continue;
-                }
-                let cost = cost + 1;
-                self.track.insert(coord, cost);
-                queue.push((cost, coord));
-            }
-        }
+        let track = &self.track;
+        let costs = search::bfs(self.start, |coord| {
+            coord
+                .neighbors()
+                .into_iter()
+                .filter(|next| track.contains_key(next))
+        });
+        self.track.extend(costs);
     }
 
-    fn parse(input: &str) -> Track {
-        let mut size_x = 0isize;
-        let mut size_y = 0isize;
-        let mut track = HashMap::new();
-        let mut walls = HashSet::new();
-        let mut start = Vec2::default();
-        let mut end = Vec2::default();
-        for (y, line) in input.lines().enumerate() {
-            for (x, ch) in line.chars().enumerate() {
-                size_x = size_x.max(x as isize + 1);
-                let v = Vec2 {
-                    x: x as isize,
-                    y: y as isize,
-                };
-                match ch {
-                    '.' => {
-                        track.insert(v, 0);
-                    }
-                    'S' => {
-                        track.insert(v, 0);
-                        start = v;
-                    }
-                    'E' => {
-                        track.insert(v, 0);
-                        end = v;
-                    }
-                    '#' => {
-                        walls.insert(v);
-                    }
-                    _ => panic!("Unknown character {ch}"),
-                }
+    fn parse(input: &str, threshold: usize) -> Result<Track, ParseError> {
+        let mut start = None;
+        let mut end = None;
+        let walls = Grid::parse(input, |pos, ch| match ch {
+            '.' => Some(false),
+            'S' => {
+                start = Some(pos);
+                Some(false)
             }
-            size_y = size_y.max(y as isize + 1);
-        }
+            'E' => {
+                end = Some(pos);
+                Some(false)
+            }
+            '#' => Some(true),
+            _ => None,
+        })?;
+        let input = Span::new(input);
+        let start = start.ok_or_else(|| input.end().error("expected a start ('S')"))?;
+        let end = end.ok_or_else(|| input.end().error("expected an end ('E')"))?;
+        let track = walls
+            .iter()
+            .filter(|(_, &wall)| !wall)
+            .map(|(pos, _)| (pos, 0))
+            .collect();
         let mut track = Track {
-            size: Vec2 {
-                x: size_x,
-                y: size_y,
-            },
             start,
             end,
             track,
             walls,
+            threshold,
         };
 
         track.assign_costs();
 
-        track
+        Ok(track)
     }
 
-    #[allow(dead_code)]
-    fn print_debug(&self) {
-        for y in 0..self.size.y {
-            print!("{y:<5}");
-            for x in 0..self.size.x {
-                let v = Vec2 { x, y };
-                let tile = if self.start == v {
-                    'S'
-                } else if self.end == v {
-                    'E'
-                } else if self.track.contains_key(&v) {
-                    '.'
-                } else {
-                    '#'
-                };
-                print!("{tile}");
+    /// The racetrack shaded from the start to the end by how long it takes to get to each tile.
+    fn render(&self) -> Image {
+        let length = self.track.get(&self.end).copied().unwrap_or(0).max(1);
+        let mut image = Image::from_grid(&self.walls, |pos, &wall| {
+            match (wall, self.track.get(&pos)) {
+                (true, _) | (false, None) => Rgb::GREY,
+                (false, Some(&cost)) => Rgb::BLUE.lerp(Rgb::YELLOW, cost as f64 / length as f64),
             }
-            println!();
-        }
+        });
+        image.set(self.start, Rgb::GREEN);
+        image.set(self.end, Rgb::RED);
+        image
     }
 }
+
+/// A single winding track with no branches, taken from a maze as the way from one corner to the
+/// room furthest from it.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let walls = maze(rng, scaled_side(70, size));
+    let start = Vec2::new(1, 1);
+    let costs = search::bfs(start, |pos| {
+        pos.neighbors()
+            .into_iter()
+            .filter(|next| walls.get(*next) == Some(&false))
+    });
+    let (&end, _) = costs.iter().max_by_key(|&(pos, cost)| (cost, pos)).unwrap();
+
+    // Follow the costs back down from the end, as there's only one way to every room in a maze.
+    let mut track = HashSet::from([end]);
+    let mut pos = end;
+    while pos != start {
+        pos = pos
+            .neighbors()
+            .into_iter()
+            .find(|next| costs.get(next) == Some(&(costs[&pos] - 1)))
+            .unwrap();
+        track.insert(pos);
+    }
+
+    Grid::from_fn(walls.width(), walls.height(), |pos| match pos {
+        _ if pos == start => 'S',
+        _ if pos == end => 'E',
+        _ if track.contains(&pos) => '.',
+        _ => '#',
+    })
+    .to_string()
+}
@@ -1,15 +1,19 @@
 // Shamelssly copied from https://github.com/michel-kraemer/adventofcode-rust/blob/main/2024/day21/src/main.rs
 // because I just couldn't wrap my head around this
 
-use std::{
-    collections::{HashMap, VecDeque},
-    rc::Rc,
-};
+use std::{collections::HashMap, rc::Rc};
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::{rng::Rng, search},
+};
 
 pub const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
 
+/// The directional key that moves along each of [`DIRS`].
+const KEYS: [u8; 4] = [b'>', b'v', b'<', b'^'];
+
 const NUMERIC: [[u8; 3]; 4] = [
     [b'7', b'8', b'9'],
     [b'4', b'5', b'6'],
@@ -19,33 +23,58 @@ const NUMERIC: [[u8; 3]; 4] = [
 
 const DIRECTIONAL: [[u8; 3]; 2] = [[b' ', b'^', b'A'], [b'<', b'v', b'>']];
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 21");
-    let data = input(21, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Vec<String>;
+
+    const DAY: u16 = 21;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(complexity(input, 2).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(complexity(input, 25).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
+}
 
-    Ok(())
+/// Codes are always three digits followed by `A`.
+fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    Span::new(input)
+        .lines()
+        .map(|line| match line.as_str().as_bytes() {
+            [a, b, c, b'A'] if [a, b, c].iter().all(|d| d.is_ascii_digit()) => {
+                Ok(line.as_str().to_string())
+            }
+            _ => Err(line.error("expected a code such as 029A")),
+        })
+        .collect()
 }
 
-fn p1(input: &str) {
+fn complexity(codes: &[String], robots: usize) -> usize {
     let mut cache = HashMap::new();
     let mut path_cache = [const { None }; 128 * 128];
-    let result = input
-        .lines()
+    codes
+        .iter()
         .map(|code| {
             (
                 code,
-                find_shortest_sequence(code.as_bytes(), 25, true, &mut cache, &mut path_cache),
+                find_shortest_sequence(code.as_bytes(), robots, true, &mut cache, &mut path_cache),
             )
         })
         .map(|(code, seq)| code[..3].parse::<usize>().unwrap() * seq)
-        .sum::<usize>();
-    println!("P1: {result}");
+        .sum::<usize>()
 }
 
-fn p2(_input: &str) {}
-
 fn find_shortest_paths(
     keypad: &[[u8; 3]],
     from: u8,
@@ -76,60 +105,36 @@ fn find_shortest_paths(
         }
     }
 
-    // flood fill keypad to find the shortest distances
-    let mut dists = vec![[usize::MAX; 3]; keypad.len()];
-    let mut queue = VecDeque::new();
-    queue.push_back((start.0, start.1, 0));
-
-    while let Some((x, y, steps)) = queue.pop_front() {
-        dists[y][x] = steps;
-        for (dx, dy) in DIRS {
-            let nx = x as i32 + dx;
-            let ny = y as i32 + dy;
-            if nx >= 0
-                && ny >= 0
-                && nx < 3
-                && ny < keypad.len() as i32
-                && keypad[ny as usize][nx as usize] != b' '
-                && dists[ny as usize][nx as usize] == usize::MAX
-            {
-                queue.push_back((nx as usize, ny as usize, steps + 1));
-            }
-        }
-    }
+    // find every shortest path over the keypad, skipping the gap
+    let shortest = search::all_shortest_paths(start, |&(x, y)| {
+        DIRS.iter()
+            .filter_map(move |(dx, dy)| {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                let key = *keypad.get(ny as usize)?.get(nx as usize)?;
+                (key != b' ').then_some(((nx as usize, ny as usize), 1))
+            })
+            .collect::<Vec<_>>()
+    });
 
-    // backtrack from 'end' back to 'start' and collect all paths
-    let mut paths = Vec::new();
-    let mut stack = Vec::new();
-    stack.push((end.0, end.1, vec![b'A']));
-    while let Some((x, y, path)) = stack.pop() {
-        if x == start.0 && y == start.1 {
-            paths.push(path);
-            continue;
-        }
-        for (i, (dx, dy)) in DIRS.iter().enumerate() {
-            let nx = x as i32 + dx;
-            let ny = y as i32 + dy;
-            if nx >= 0
-                && ny >= 0
-                && nx < 3
-                && ny < keypad.len() as i32
-                && dists[ny as usize][nx as usize] < dists[y][x]
-            {
-                // do everything in reverse
-                let c = match i {
-                    0 => b'<',
-                    1 => b'^',
-                    2 => b'>',
-                    3 => b'v',
-                    _ => panic!(),
-                };
-                let mut new_path = vec![c];
-                new_path.extend(&path);
-                stack.push((nx as usize, ny as usize, new_path));
-            }
-        }
-    }
+    // turn each path into the keys that walk it, then press 'A'
+    let paths: Vec<Vec<u8>> = shortest
+        .paths(&end)
+        .into_iter()
+        .map(|path| {
+            path.windows(2)
+                .filter_map(|step| {
+                    let delta = (
+                        step[1].0 as i32 - step[0].0 as i32,
+                        step[1].1 as i32 - step[0].1 as i32,
+                    );
+                    let i = DIRS.iter().position(|&d| d == delta)?;
+                    Some(KEYS[i])
+                })
+                .chain([b'A'])
+                .collect()
+        })
+        .collect();
 
     let result = Rc::new(paths);
     cache[from as usize * 128 + to as usize] = Some(result.clone());
@@ -174,3 +179,105 @@ fn find_shortest_sequence(
 
     result
 }
+
+/// Door codes of three digits and an A.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..5 * size)
+        .map(|_| format!("{:03}A\n", rng.below(1000)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Where a robot arm is: the keypad it's over, and the row and column it's pointing at.
+    type Arm = (usize, usize);
+
+    fn key_at(keypad: &[[u8; 3]], (x, y): Arm) -> u8 {
+        keypad[y][x]
+    }
+
+    fn find_key(keypad: &[[u8; 3]], key: u8) -> Arm {
+        (0..keypad.len())
+            .flat_map(|y| (0..3).map(move |x| (x, y)))
+            .find(|&arm| key_at(keypad, arm) == key)
+            .unwrap()
+    }
+
+    /// The fewest presses it takes to type `code` through `robots` directional keypads, found by
+    /// searching every position of every arm in the chain at once rather than with
+    /// [`find_shortest_sequence`]. Each state is the arm over each directional keypad, then the arm
+    /// over the numeric keypad, then how much of the code has been typed.
+    fn brute_force_presses(code: &[u8], robots: usize) -> usize {
+        let mut start = vec![find_key(&DIRECTIONAL, b'A'); robots];
+        start.push(find_key(&NUMERIC, b'A'));
+        let distances = search::bfs((start, 0), |(arms, typed)| {
+            [b'>', b'v', b'<', b'^', b'A']
+                .into_iter()
+                .filter_map(|pressed| press(arms, *typed, pressed, code))
+                .collect::<Vec<_>>()
+        });
+        distances
+            .into_iter()
+            .filter(|((_, typed), _)| *typed == code.len())
+            .map(|(_, presses)| presses)
+            .min()
+            .unwrap()
+    }
+
+    /// What pressing a key on the first directional keypad does to the chain, or `None` if it
+    /// points an arm at a gap or off the keypad, or types the wrong digit.
+    fn press(
+        arms: &[Arm],
+        typed: usize,
+        mut pressed: u8,
+        code: &[u8],
+    ) -> Option<(Vec<Arm>, usize)> {
+        let mut arms = arms.to_vec();
+        let last = arms.len() - 1;
+        for (i, arm) in arms.iter_mut().enumerate() {
+            let keypad: &[[u8; 3]] = if i == last { &NUMERIC } else { &DIRECTIONAL };
+            if pressed == b'A' {
+                pressed = key_at(keypad, *arm);
+                continue;
+            }
+            let (dx, dy) = DIRS[KEYS.iter().position(|&key| key == pressed)?];
+            let moved = (
+                arm.0.checked_add_signed(dx as isize)?,
+                arm.1.checked_add_signed(dy as isize)?,
+            );
+            if *keypad.get(moved.1)?.get(moved.0)? == b' ' {
+                return None;
+            }
+            *arm = moved;
+            return Some((arms, typed));
+        }
+        (typed < code.len() && pressed == code[typed]).then_some((arms, typed + 1))
+    }
+
+    #[test]
+    fn test_shortest_sequence_matches_brute_force() {
+        // The example from the puzzle, typed through two robots.
+        assert_eq!(brute_force_presses(b"029A", 2), 68);
+        for seed in 0..10 {
+            for code in parse(&generate(&mut Rng::new(seed), 1)).unwrap() {
+                for robots in 0..=3 {
+                    let mut cache = HashMap::new();
+                    let mut path_cache = [const { None }; 128 * 128];
+                    assert_eq!(
+                        find_shortest_sequence(
+                            code.as_bytes(),
+                            robots,
+                            true,
+                            &mut cache,
+                            &mut path_cache
+                        ),
+                        brute_force_presses(code.as_bytes(), robots),
+                        "{code} through {robots} robots",
+                    );
+                }
+            }
+        }
+    }
+}
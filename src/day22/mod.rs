@@ -2,42 +2,67 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::{parallel, rng::Rng},
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 22");
-    let data = input(22, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = Vec<SecretNum>;
+
+    const DAY: u16 = 22;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        p2(input)
+            .map(Answer::from)
+            .ok_or(anyhow::anyhow!("No buyers"))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let input = parse(input);
-    let result = input
+fn p1(input: &[SecretNum]) -> i64 {
+    parallel::map(input, |secret_num| secret_num.nth(2000).0)
         .into_iter()
-        .map(|secret_num| secret_num.nth(2000))
-        .map(|s| s.0)
-        .sum::<i64>();
-    println!("P1: {result}");
+        .sum::<i64>()
 }
 
-fn p2(input: &str) {
-    let input = parse(input);
-    let mut cache = HashMap::new();
-    for num in input {
-        cache_best_price_changes_for_secret_number(num, 2000, &mut cache);
-    }
+fn p2(input: &[SecretNum]) -> Option<i64> {
+    let cache = parallel::fold(
+        input,
+        HashMap::new,
+        |mut cache, num| {
+            cache_best_price_changes_for_secret_number(*num, 2000, &mut cache);
+            cache
+        },
+        |mut cache, other| {
+            for (key, bananas) in other {
+                *cache.entry(key).or_default() += bananas;
+            }
+            cache
+        },
+    );
     // let sn = find_best_price_changes(&cache);
-    let sn = cache.values().max().unwrap();
-    println!("{sn:#?}")
+    cache.values().max().copied()
 }
 
-fn parse(input: &str) -> Vec<SecretNum> {
-    input
+fn parse(input: &str) -> Result<Vec<SecretNum>, ParseError> {
+    Span::new(input)
         .lines()
-        .map(|line| SecretNum(line.parse().unwrap()))
+        .map(|line| Ok(SecretNum(line.parse()?)))
         .collect()
 }
 
@@ -91,7 +116,7 @@ fn secret_num_prices(sn: SecretNum, n: usize) -> Vec<i64> {
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-struct SecretNum(i64);
+pub struct SecretNum(i64);
 
 impl SecretNum {
     fn mix(&self, other: i64) -> SecretNum {
@@ -134,6 +159,13 @@ impl SecretNum {
     }
 }
 
+/// Initial secret numbers, which are below 2^24 like every number the buyers go on to make.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..1800 * size)
+        .map(|_| format!("{}\n", rng.range(1..1 << 24)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,43 +2,58 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 23");
-    let data = input(23, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
+impl Solution for Day {
+    type Input = HashMap<String, HashSet<String>>;
+
+    const DAY: u16 = 23;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p1(input: &str) {
-    let graph = parse(input);
-    let connections = interconnected_computers(3, &graph);
-    println!("P1: {}", connections.len());
+fn p1(graph: &HashMap<String, HashSet<String>>) -> usize {
+    interconnected_computers(3, graph).len()
 }
 
-fn p2(input: &str) {
-    let graph = parse(input);
-    let neighbors = find_maximum_clique(&graph);
-    let password = neighbors.into_iter().sorted().join(",");
-    println!("P2: {:?}", password);
+fn p2(graph: &HashMap<String, HashSet<String>>) -> String {
+    let neighbors = find_maximum_clique(graph);
+    neighbors.into_iter().sorted().join(",")
 }
 
-fn parse(input: &str) -> HashMap<String, HashSet<String>> {
+fn parse(input: &str) -> Result<HashMap<String, HashSet<String>>, ParseError> {
     let mut map: HashMap<String, HashSet<String>> = HashMap::new();
 
-    for line in input.lines() {
-        let (l, r) = line.split_once('-').unwrap();
-        let l = l.to_string();
-        let r = r.to_string();
+    for line in Span::new(input).lines() {
+        let (l, r) = line.split_once("-")?;
+        let l = l.as_str().to_string();
+        let r = r.as_str().to_string();
 
         map.entry(l.clone()).or_default().insert(r.clone());
         map.entry(r.clone()).or_default().insert(l.clone());
     }
 
-    map
+    Ok(map)
 }
 
 fn find_maximum_clique(graph: &HashMap<String, HashSet<String>>) -> HashSet<String> {
@@ -147,3 +162,45 @@ fn find_connections(num: usize, graph: &HashMap<String, HashSet<String>>) -> Vec
 
     results
 }
+
+/// A network of computers named with two letters, or more once those run out, each linked to
+/// about a dozen random others, plus a hidden LAN party of thirteen. Finding the party takes time
+/// about cubic in the number of computers, so that grows with the cube root of the size, to keep
+/// the time to solve it growing linearly.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = (520.0 * (size as f64).cbrt()).round() as usize;
+    let letters = (2..).find(|&n| 26usize.pow(n) >= count).unwrap();
+    let mut names = (0..26usize.pow(letters))
+        .map(|n| {
+            (0..letters)
+                .rev()
+                .map(|i| char::from(b'a' + (n / 26usize.pow(i) % 26) as u8))
+                .collect::<String>()
+        })
+        .collect_vec();
+    rng.shuffle(&mut names);
+    names.truncate(count);
+
+    let mut links = HashSet::new();
+    for a in 0..count {
+        for _ in 0..6 {
+            let b = rng.index(count);
+            if a != b {
+                links.insert((a.min(b), a.max(b)));
+            }
+        }
+    }
+    let mut party = (0..count).collect_vec();
+    rng.shuffle(&mut party);
+    for (&a, &b) in party[..13].iter().tuple_combinations() {
+        links.insert((a.min(b), a.max(b)));
+    }
+
+    let mut lines = links
+        .into_iter()
+        .map(|(a, b)| format!("{}-{}\n", names[a], names[b]))
+        .collect_vec();
+    lines.sort();
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
@@ -1,57 +1,90 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use itertools::Itertools;
 
-use crate::utils::input;
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
 
-pub fn run(example: bool) -> anyhow::Result<()> {
-    println!("Day 24");
-    let data = input(24, example)?;
-    p1(&data);
-    p2(&data);
+pub struct Day;
 
-    Ok(())
-}
+impl Solution for Day {
+    type Input = HashMap<String, Value>;
 
-fn p1(input: &str) {
-    let mut wires = parse(input);
-    execute(&mut wires);
-    let result = calc_decimal('z', &wires);
-    println!("P1: {:#?}", result);
+    const DAY: u16 = 24;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p2(input).into())
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
 }
 
-fn p2(input: &str) {
-    let mut wires = parse(input);
+fn p1(wires: &HashMap<String, Value>) -> i64 {
+    let mut wires = wires.clone();
     execute(&mut wires);
-    faulty_wires(&wires);
-    // println!("P2: {:#?}", result);
+    calc_decimal('z', &wires)
 }
 
-fn faulty_wires(wires: &HashMap<String, Value>) {
-    println!("Checking for faulty wires");
-    let x = calc_decimal('x', wires);
-    let y = calc_decimal('y', wires);
-    let z = calc_decimal('z', wires);
-    let expected = x + y;
-    println!("Expected: {:b}", expected);
-    println!("Actual  : {:b}", z);
-
-    for i in 0i64..64 {
-        let mask = 1 << i;
-        let z = z & mask;
-        let expected = expected & mask;
-        if expected != z {
-            print!("z{:02},", i);
-        }
-    }
-    println!();
-}
+/// The eight wires whose gates were swapped. The circuit is a ripple carry adder, in which every
+/// gate has a fixed place: an XOR either reads the inputs and feeds the next XOR, or writes a `z`
+/// bit, and an AND feeds an OR, which carries into the next bit. Any gate out of place has had its
+/// output swapped.
+fn p2(wires: &HashMap<String, Value>) -> String {
+    let gates = wires
+        .iter()
+        .filter_map(|(out, value)| match value {
+            Value::Op(op) => Some((out.as_str(), op)),
+            Value::Num(_) => None,
+        })
+        .collect_vec();
+    let feeds = |wire: &str, is_op: fn(&Op) -> bool| {
+        gates.iter().any(|(_, op)| {
+            let (l, r) = op.inputs();
+            is_op(op) && (l == wire || r == wire)
+        })
+    };
+    // The last carry goes straight out as the highest bit.
+    let last_z = wires
+        .keys()
+        .filter(|w| w.starts_with('z'))
+        .max()
+        .map(String::as_str);
 
-fn check_result(wires: &HashMap<String, Value>) -> bool {
-    let x = calc_decimal('x', wires);
-    let y = calc_decimal('y', wires);
-    let z = calc_decimal('z', wires);
-    x + y == z
+    gates
+        .iter()
+        .filter(|&&(out, op)| {
+            let (l, r) = op.inputs();
+            let from_inputs = [l, r].iter().all(|w| w.starts_with(['x', 'y']));
+            // The first bit has no carry coming in, so it's only half an adder.
+            let first_bit = from_inputs && l.ends_with("00");
+            match op {
+                Op::Xor(..) if from_inputs => {
+                    !first_bit && !feeds(out, |op| matches!(op, Op::Xor(..)))
+                }
+                Op::Xor(..) => !out.starts_with('z'),
+                Op::And(..) => {
+                    out.starts_with('z')
+                        || (!first_bit && !feeds(out, |op| matches!(op, Op::Or(..))))
+                }
+                Op::Or(..) => out.starts_with('z') && Some(out) != last_z,
+            }
+        })
+        .map(|(out, _)| out)
+        .sorted()
+        .join(",")
 }
 
 fn calc_decimal(prefix: char, wires: &HashMap<String, Value>) -> i64 {
@@ -77,28 +110,107 @@ fn execute(wires: &mut HashMap<String, Value>) {
     }
 }
 
-fn parse(input: &str) -> HashMap<String, Value> {
-    input
-        .lines()
-        .filter_map(|line| {
-            if line.is_empty() {
-                return None;
-            }
-            let s = if line.contains(":") {
-                let (wire, value) = line.split_once(": ").unwrap();
-                (wire.to_string(), Value::Num(value.parse().unwrap()))
-            } else {
-                let (op, wire) = line.split_once(" -> ").unwrap();
-                let op = Op::parse(op);
-                (wire.to_string(), Value::Op(op))
+fn parse(input: &str) -> Result<HashMap<String, Value>, ParseError> {
+    let mut wires = HashMap::new();
+    let mut inputs = Vec::new();
+    let mut gate_lines = Vec::new();
+    for line in Span::new(input).lines() {
+        if line.is_empty() {
+            continue;
+        }
+        let (wire, value) = if line.as_str().contains(":") {
+            let (wire, value) = line.split_once(": ")?;
+            let value = match value.parse()? {
+                bit @ (0 | 1) => bit,
+                _ => return Err(value.error("expected 0 or 1")),
             };
-            Some(s)
-        })
-        .collect()
+            (wire, Value::Num(value))
+        } else {
+            let (op, wire) = line.split_once(" -> ")?;
+            let (op, left, right) = Op::parse(op)?;
+            inputs.extend([left, right]);
+            gate_lines.push((wire.as_str().to_string(), line));
+            (wire, Value::Op(op))
+        };
+        if wires.insert(wire.as_str().to_string(), value).is_some() {
+            return Err(wire.error("expected a wire that's only set once"));
+        }
+    }
+
+    // Every gate's inputs have to lead somewhere, or executing it would have nothing to read.
+    if let Some(wire) = inputs.iter().find(|w| !wires.contains_key(w.as_str())) {
+        return Err(wire.error("expected a wire that's defined somewhere"));
+    }
+    check_loops(&wires, &gate_lines)?;
+
+    Ok(wires)
+}
+
+/// Fails on the line of a gate that ends up reading its own output, which would never settle.
+/// This sorts the gates so that each one comes after the gates it reads, and anything left over
+/// leads back round to itself.
+fn check_loops(
+    wires: &HashMap<String, Value>,
+    gate_lines: &[(String, Span)],
+) -> Result<(), ParseError> {
+    let gate_inputs = |wire: &str| match &wires[wire] {
+        Value::Op(op) => {
+            let (l, r) = op.inputs();
+            [l, r]
+                .into_iter()
+                .filter(|w| matches!(wires[*w], Value::Op(_)))
+                .collect_vec()
+        }
+        Value::Num(_) => Vec::new(),
+    };
+
+    let mut waiting = HashMap::new();
+    let mut readers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (wire, _) in gate_lines {
+        let inputs = gate_inputs(wire);
+        waiting.insert(wire.as_str(), inputs.len());
+        for input in inputs {
+            readers.entry(input).or_default().push(wire);
+        }
+    }
+    let mut ready = waiting
+        .iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&wire, _)| wire)
+        .collect_vec();
+    while let Some(wire) = ready.pop() {
+        waiting.remove(wire);
+        for &reader in readers.get(wire).into_iter().flatten() {
+            let count = waiting.get_mut(reader).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push(reader);
+            }
+        }
+    }
+
+    // Every gate left over reads another one that's left over, so following them backwards has
+    // to come round to a gate it's already been through, which is on the loop.
+    let Some((start, _)) = gate_lines
+        .iter()
+        .find(|(wire, _)| waiting.contains_key(wire.as_str()))
+    else {
+        return Ok(());
+    };
+    let mut seen = HashSet::new();
+    let mut wire = start.as_str();
+    while seen.insert(wire) {
+        wire = gate_inputs(wire)
+            .into_iter()
+            .find(|input| waiting.contains_key(input))
+            .unwrap();
+    }
+    let (_, line) = gate_lines.iter().find(|(gate, _)| gate == wire).unwrap();
+    Err(line.error("expected gates that don't loop back on themselves"))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Value {
+pub enum Value {
     Op(Op),
     Num(i64),
 }
@@ -113,19 +225,21 @@ impl Value {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Op {
+pub enum Op {
     And(String, String),
     Or(String, String),
     Xor(String, String),
 }
 
 impl Op {
+    fn inputs(&self) -> (&str, &str) {
+        match self {
+            Op::And(l, r) | Op::Or(l, r) | Op::Xor(l, r) => (l, r),
+        }
+    }
+
     fn exec(&self, label: &str, wires: &mut HashMap<String, Value>) -> i64 {
-        let (l, r) = match self {
-            Op::And(l, r) => (l, r),
-            Op::Or(l, r) => (l, r),
-            Op::Xor(l, r) => (l, r),
-        };
+        let (l, r) = self.inputs();
         let l = match wires.get(l).unwrap().clone() {
             Value::Op(op) => op.exec(l, wires),
             Value::Num(i) => i,
@@ -144,22 +258,133 @@ impl Op {
         result
     }
 
-    // fn extract_nums(&self, wires: &mut HashMap<String, Value>) -> Option<i64, i64> {
-    //     let (l,r) = match self {
-    //         Op::And(l,r) => (l,r),
-    //         Op::Or(l,r,) => (l,r),
-    //         Op::Xor(l,r,) => (l,r),
-    //     };
-    //     wires.get(l).and
-    // }
-
-    fn parse(input: &str) -> Op {
-        let (left, op, right) = input.split(" ").collect_tuple().unwrap();
-        match op {
-            "AND" => Op::And(left.to_string(), right.to_string()),
-            "OR" => Op::Or(left.to_string(), right.to_string()),
-            "XOR" => Op::Xor(left.to_string(), right.to_string()),
-            _ => panic!("Unknown op: {}", op),
+    /// Parses a gate, also returning the spans of its two inputs.
+    fn parse(input: Span) -> Result<(Op, Span, Span), ParseError> {
+        let Some((left, op, right)) = input.split(" ").collect_tuple() else {
+            return Err(input.error("expected a gate such as `x00 AND y00`"));
+        };
+        let (l, r) = (left.as_str().to_string(), right.as_str().to_string());
+        let op = match op.as_str() {
+            "AND" => Op::And(l, r),
+            "OR" => Op::Or(l, r),
+            "XOR" => Op::Xor(l, r),
+            _ => return Err(op.error("expected AND, OR or XOR")),
+        };
+        Ok((op, left, right))
+    }
+}
+
+fn generate(rng: &mut Rng, size: usize) -> String {
+    swapped_adder(rng, size).0
+}
+
+/// A ripple carry adder like the real one, with random input bits and the outputs of four pairs of
+/// gates swapped, along with the swapped wires in order. Bigger sizes add bits up to 60, as many
+/// as the answers hold.
+fn swapped_adder(rng: &mut Rng, size: usize) -> (String, Vec<String>) {
+    let bits = (45 * size).min(60);
+    let mut used = HashSet::new();
+    let mut name = |rng: &mut Rng| loop {
+        let name = (0..3)
+            .map(|_| char::from(b'a' + rng.below(23) as u8))
+            .collect::<String>();
+        if used.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    // Each bit's gates as (left, op, right, output). Bit 0 is only a half adder.
+    let mut adders = vec![vec![
+        (
+            "x00".to_string(),
+            "XOR",
+            "y00".to_string(),
+            "z00".to_string(),
+        ),
+        ("x00".to_string(), "AND", "y00".to_string(), name(rng)),
+    ]];
+    for bit in 1..bits {
+        let (x, y, z) = (
+            format!("x{bit:02}"),
+            format!("y{bit:02}"),
+            format!("z{bit:02}"),
+        );
+        let carry_in = adders[bit - 1].last().unwrap().3.clone();
+        let carry_out = if bit == bits - 1 {
+            format!("z{bits:02}")
+        } else {
+            name(rng)
+        };
+        let (sum, both, carried) = (name(rng), name(rng), name(rng));
+        adders.push(vec![
+            (x.clone(), "XOR", y.clone(), sum.clone()),
+            (x, "AND", y, both.clone()),
+            (sum.clone(), "XOR", carry_in.clone(), z),
+            (sum, "AND", carry_in, carried.clone()),
+            (both, "OR", carried, carry_out),
+        ]);
+    }
+
+    // Swapping outputs within a bit's adder never makes a loop, as long as neither gate reads the
+    // other's output.
+    const SWAPS: &[(usize, usize)] = &[(0, 1), (2, 4), (2, 3), (2, 1)];
+    let mut swapped = (1..bits - 1).collect_vec();
+    rng.shuffle(&mut swapped);
+    let mut swapped_wires = Vec::new();
+    for &bit in swapped.iter().take(4) {
+        let &(a, b) = rng.choose(SWAPS);
+        let out_a = adders[bit][a].3.clone();
+        adders[bit][a].3 = std::mem::replace(&mut adders[bit][b].3, out_a);
+        swapped_wires.extend([adders[bit][a].3.clone(), adders[bit][b].3.clone()]);
+    }
+    swapped_wires.sort();
+
+    let mut inputs = String::new();
+    for wire in ['x', 'y'] {
+        for bit in 0..bits {
+            inputs += &format!("{wire}{bit:02}: {}\n", rng.below(2));
+        }
+    }
+    let mut gates = adders
+        .into_iter()
+        .flatten()
+        .map(|(left, op, right, out)| {
+            let (left, right) = if rng.chance(0.5) {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            format!("{left} {op} {right} -> {out}\n")
+        })
+        .collect_vec();
+    rng.shuffle(&mut gates);
+    (format!("{inputs}\n{}", gates.concat()), swapped_wires)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loops() {
+        let error = parse("x00: 1\n\nx00 AND aaa -> aaa\n").unwrap_err();
+        assert_eq!(error.line, 3);
+
+        let input = "x00: 1\ny00: 0\n\nx00 OR y00 -> zzz\nccc XOR zzz -> z00\nbbb AND zzz -> ccc\nz00 OR x00 -> bbb\n";
+        let error = parse(input).unwrap_err();
+        assert!((5..=7).contains(&error.line), "{error}");
+        assert!(parse(&input.replace("ccc XOR zzz", "x00 XOR zzz")).is_ok());
+
+        let error = parse("x00: 1\n\nx00 OR x00 -> x00\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 15));
+    }
+
+    #[test]
+    fn test_finds_swapped_wires() {
+        for seed in 0..20 {
+            let (input, swapped) = swapped_adder(&mut Rng::new(seed), 1);
+            let wires = parse(&input).unwrap();
+            assert_eq!(p2(&wires), swapped.join(","), "seed {seed}");
         }
     }
 }
@@ -0,0 +1,226 @@
+use itertools::Itertools;
+
+use crate::{
+    parse::{ParseError, Span},
+    solution::{Answer, Params, Solution},
+    utils::rng::Rng,
+};
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input = Schematics;
+
+    const DAY: u16 = 25;
+
+    const PARTS: u8 = 1;
+
+    fn parse(&self, input: &str, _params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(p1(input).into())
+    }
+
+    fn part2(&self, _input: &Self::Input) -> anyhow::Result<Answer> {
+        anyhow::bail!("Day 25 has no part 2")
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok(generate(rng, size))
+    }
+}
+
+const WIDTH: usize = 5;
+const HEIGHT: usize = 7;
+
+/// The height of each pin of a lock, or of each cut of a key, not counting the full row along
+/// the top of a lock or the bottom of a key.
+type Heights = [u8; WIDTH];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Schematics {
+    locks: Vec<Heights>,
+    keys: Vec<Heights>,
+}
+
+fn p1(schematics: &Schematics) -> usize {
+    schematics
+        .locks
+        .iter()
+        .map(|lock| schematics.keys.iter().filter(|key| fits(lock, key)).count())
+        .sum()
+}
+
+/// Whether a key fits a lock without any of its columns overlapping the lock's pins.
+fn fits(lock: &Heights, key: &Heights) -> bool {
+    lock.iter()
+        .zip(key)
+        .all(|(pin, cut)| (pin + cut) as usize <= HEIGHT - 2)
+}
+
+fn parse(input: &str) -> Result<Schematics, ParseError> {
+    let input = Span::new(input);
+    let mut schematics = Schematics::default();
+    let mut block = Vec::new();
+    for line in input.lines().chain([input.end()]) {
+        if !line.is_empty() {
+            block.push(line);
+            continue;
+        }
+        if block.is_empty() {
+            continue;
+        }
+        let (is_lock, heights) = parse_schematic(&block)?;
+        if is_lock {
+            schematics.locks.push(heights);
+        } else {
+            schematics.keys.push(heights);
+        }
+        block.clear();
+    }
+
+    if schematics.locks.is_empty() && schematics.keys.is_empty() {
+        return Err(input.end().error("expected a lock or key schematic"));
+    }
+    Ok(schematics)
+}
+
+/// Reads one block of lines as a lock, which has its top row filled, or a key, which has its
+/// bottom row filled.
+fn parse_schematic(block: &[Span]) -> Result<(bool, Heights), ParseError> {
+    if block.len() != HEIGHT {
+        return Err(block[0].error(format!("expected a schematic {HEIGHT} rows tall")));
+    }
+
+    let mut filled = [[false; WIDTH]; HEIGHT];
+    for (row, line) in filled.iter_mut().zip(block) {
+        if line.as_str().len() != WIDTH {
+            return Err(line.error(format!("expected a row of {WIDTH} cells")));
+        }
+        for (cell, (span, ch)) in row.iter_mut().zip(line.chars()) {
+            *cell = match ch {
+                '#' => true,
+                '.' => false,
+                _ => return Err(span.error("expected '#' or '.'")),
+            };
+        }
+    }
+
+    let full = |row: &[bool; WIDTH]| row.iter().all(|&cell| cell);
+    let empty = |row: &[bool; WIDTH]| row.iter().all(|&cell| !cell);
+    let (top, bottom) = (&filled[0], &filled[HEIGHT - 1]);
+    let is_lock = if full(top) && empty(bottom) {
+        true
+    } else if empty(top) && full(bottom) {
+        false
+    } else {
+        return Err(block[0].error("expected a lock or key with a full top or bottom row"));
+    };
+
+    let mut heights = [0; WIDTH];
+    for (x, height) in heights.iter_mut().enumerate() {
+        *height = filled.iter().filter(|row| row[x]).count() as u8 - 1;
+    }
+    Ok((is_lock, heights))
+}
+
+/// Locks and keys with random pin and cut heights, in about equal numbers.
+fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..500 * size)
+        .map(|_| {
+            let is_lock = rng.chance(0.5);
+            let heights = [(); WIDTH].map(|_| rng.below(HEIGHT as u64 - 1) as usize);
+            (0..HEIGHT)
+                .map(|row| {
+                    let line = heights
+                        .iter()
+                        .map(|&height| {
+                            let filled = if is_lock {
+                                row <= height
+                            } else {
+                                row >= HEIGHT - 1 - height
+                            };
+                            if filled {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                        .collect::<String>();
+                    line + "\n"
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        #####\n\
+        .####\n\
+        .####\n\
+        .####\n\
+        .#.#.\n\
+        .#...\n\
+        .....\n\
+        \n\
+        #####\n\
+        ##.##\n\
+        .#.##\n\
+        ...##\n\
+        ...#.\n\
+        ...#.\n\
+        .....\n\
+        \n\
+        .....\n\
+        #....\n\
+        #....\n\
+        #...#\n\
+        #.#.#\n\
+        #.###\n\
+        #####\n\
+        \n\
+        .....\n\
+        .....\n\
+        #.#..\n\
+        ###..\n\
+        ###.#\n\
+        ###.#\n\
+        #####\n\
+        \n\
+        .....\n\
+        .....\n\
+        .....\n\
+        #....\n\
+        #.#..\n\
+        #.#.#\n\
+        #####\n";
+
+    #[test]
+    fn test_parse() {
+        let schematics = parse(EXAMPLE).unwrap();
+        assert_eq!(schematics.locks, [[0, 5, 3, 4, 3], [1, 2, 0, 5, 3]]);
+        assert_eq!(
+            schematics.keys,
+            [[5, 0, 2, 1, 3], [4, 3, 4, 0, 2], [3, 0, 2, 0, 1]]
+        );
+
+        assert!(parse("").is_err());
+        assert!(parse("#####\n.....\n").is_err());
+        let error = parse(&EXAMPLE.replacen(".####", ".#x##", 1)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
+
+    #[test]
+    fn test_p1() {
+        let schematics = parse(EXAMPLE).unwrap();
+        assert_eq!(p1(&schematics), 3);
+        assert!(fits(&[0, 5, 3, 4, 3], &[3, 0, 2, 0, 1]));
+        assert!(!fits(&[0, 5, 3, 4, 3], &[5, 0, 2, 1, 3]));
+    }
+}
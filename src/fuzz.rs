@@ -0,0 +1,25 @@
+//! Entry points for the fuzz targets in `fuzz/`, one per day, which throw arbitrary input at the
+//! day's parser and, when it's accepted, at both parts. A day can reject whatever it likes, but has
+//! to do it with an error: panicking, overflowing or never returning is a bug.
+
+use crate::params::Params;
+
+/// Inputs longer than this are skipped, so a run spends its time on odd inputs rather than big
+/// ones that are merely slow to solve.
+pub const MAX_INPUT: usize = 4096;
+
+/// Parses `input` as the given day with the default parameters and, if that succeeds, solves each
+/// of the day's parts, throwing away the results.
+pub fn run(day: u16, input: &str) {
+    let puzzle = crate::find(day).unwrap_or_else(|| panic!("Day {day} isn't registered"));
+    if input.len() > MAX_INPUT {
+        return;
+    }
+    let Ok(parsed) = puzzle.parse(input, &Params::new()) else {
+        return;
+    };
+    let _ = parsed.part1();
+    if puzzle.parts() >= 2 {
+        let _ = parsed.part2();
+    }
+}
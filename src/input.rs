@@ -0,0 +1,380 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+
+/// Where puzzle inputs come from. Providers are shared between the threads that run days in
+/// parallel.
+pub trait InputProvider: Sync {
+    fn input(&self, day: u16, kind: &InputKind) -> anyhow::Result<String>;
+
+    /// The names of every example this provider has for `day`.
+    fn examples(&self, _day: u16) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Which of a day's inputs to use. A day can have any number of examples: `NNe.txt` is the one
+/// named `e`, `NNe2.txt` is named `e2`, and `NNe/name.txt` is named `name`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InputKind {
+    Real,
+    Example(String),
+}
+
+impl InputKind {
+    /// The name of the example in `NNe.txt`.
+    pub const DEFAULT_EXAMPLE: &str = "e";
+
+    pub fn example() -> InputKind {
+        InputKind::Example(InputKind::DEFAULT_EXAMPLE.to_string())
+    }
+
+    pub fn is_example(&self) -> bool {
+        matches!(self, InputKind::Example(_))
+    }
+
+    /// A short name for the input: empty for the real one, and the example's name otherwise.
+    pub fn suffix(&self) -> &str {
+        match self {
+            InputKind::Real => "",
+            InputKind::Example(name) => name,
+        }
+    }
+
+    /// Where the input can be found relative to an input directory, in the order to try them.
+    pub fn file_names(&self, day: u16) -> Vec<PathBuf> {
+        match self {
+            InputKind::Real => vec![format!("{day:02}.txt").into()],
+            InputKind::Example(name) => vec![
+                format!("{day:02}{name}.txt").into(),
+                Path::new(&format!("{day:02}e")).join(format!("{name}.txt")),
+            ],
+        }
+    }
+}
+
+impl Display for InputKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputKind::Real => write!(f, "real"),
+            InputKind::Example(name) if name == InputKind::DEFAULT_EXAMPLE => write!(f, "example"),
+            InputKind::Example(name) => write!(f, "example {name}"),
+        }
+    }
+}
+
+/// An input that isn't in any of the directories that were searched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputNotFound {
+    pub tried: Vec<PathBuf>,
+}
+
+impl Display for InputNotFound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Can't find the input, tried:")?;
+        for path in &self.tried {
+            write!(f, "\n  {}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InputNotFound {}
+
+/// Reads inputs from the first of several directories that has the file.
+#[derive(Debug, Clone)]
+pub struct FsProvider {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl FsProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> FsProvider {
+        FsProvider {
+            dirs: vec![dir.into()],
+        }
+    }
+
+    /// Searches, in order: `$AOC_INPUT_DIR`, `input_dir`, `inputs/` next to the executable and
+    /// `inputs/` in this crate's source, so it works from any working directory.
+    pub fn resolve(input_dir: Option<&Path>) -> FsProvider {
+        let env = std::env::var_os("AOC_INPUT_DIR").map(PathBuf::from);
+        let exe = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.join("inputs")));
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let dirs = [env, input_dir.map(Path::to_path_buf), exe, Some(manifest)];
+        FsProvider {
+            dirs: dirs.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Where new inputs should be saved: the first directory that exists, or the first one to
+    /// be searched if none of them do.
+    pub fn save_dir(&self) -> Option<&Path> {
+        self.dirs
+            .iter()
+            .find(|dir| dir.is_dir())
+            .or(self.dirs.first())
+            .map(PathBuf::as_path)
+    }
+}
+
+impl Default for FsProvider {
+    fn default() -> Self {
+        FsProvider::resolve(None)
+    }
+}
+
+impl InputProvider for FsProvider {
+    fn input(&self, day: u16, kind: &InputKind) -> anyhow::Result<String> {
+        let names = kind.file_names(day);
+        let tried = self
+            .dirs
+            .iter()
+            .flat_map(|dir| names.iter().map(|name| dir.join(name)));
+        for path in tried.clone() {
+            if path.is_file() {
+                return std::fs::read_to_string(&path)
+                    .with_context(|| format!("Can't read {}", path.display()));
+            }
+        }
+        Err(InputNotFound {
+            tried: tried.collect(),
+        }
+        .into())
+    }
+
+    fn examples(&self, day: u16) -> Vec<String> {
+        let prefix = format!("{day:02}e");
+        let mut names = BTreeSet::new();
+        for dir in &self.dirs {
+            for stem in txt_stems(dir) {
+                if let Some(rest) = stem.strip_prefix(&prefix) {
+                    names.insert(format!("{}{rest}", InputKind::DEFAULT_EXAMPLE));
+                }
+            }
+            names.extend(txt_stems(&dir.join(&prefix)));
+        }
+        names.into_iter().collect()
+    }
+}
+
+/// The names of the `.txt` files in `dir`, without the extension.
+fn txt_stems(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            Some(name.strip_suffix(".txt")?.to_string())
+        })
+        .collect()
+}
+
+/// Reads the whole of standard input, whatever the day. It can only be read once, so it's only
+/// useful for solving a single day.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdinProvider;
+
+impl InputProvider for StdinProvider {
+    fn input(&self, _day: u16, _kind: &InputKind) -> anyhow::Result<String> {
+        Ok(std::io::read_to_string(std::io::stdin())?)
+    }
+}
+
+/// Downloads real inputs from an Advent of Code compatible server and keeps them in `cache`, so
+/// each input is only ever downloaded once. Examples aren't served, so they're only read from the
+/// cache.
+#[derive(Debug, Clone)]
+pub struct HttpProvider {
+    pub base_url: String,
+    pub session: String,
+    pub cache: FsProvider,
+}
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+impl HttpProvider {
+    fn url(&self, day: u16) -> String {
+        format!(
+            "{}/2024/day/{day}/input",
+            self.base_url.trim_end_matches('/')
+        )
+    }
+
+    fn download(&self, day: u16) -> anyhow::Result<String> {
+        let url = self.url(day);
+        let mut response = ureq::get(&url)
+            .header("Cookie", format!("session={}", self.session))
+            .header("User-Agent", "github.com/ursuscamp/aoc2024")
+            .call()
+            .with_context(|| format!("Can't download {url}"))?;
+        response
+            .body_mut()
+            .read_to_string()
+            .with_context(|| format!("Can't download {url}"))
+    }
+}
+
+impl InputProvider for HttpProvider {
+    fn input(&self, day: u16, kind: &InputKind) -> anyhow::Result<String> {
+        match self.cache.input(day, kind) {
+            Err(e) if e.is::<InputNotFound>() => {}
+            cached => return cached,
+        }
+        let Some(dir) = self.cache.save_dir() else {
+            bail!("There's nowhere to save downloaded inputs");
+        };
+        let path = dir.join(&kind.file_names(day)[0]);
+        if kind.is_example() {
+            bail!(
+                "Example inputs can't be downloaded, save the example to {}",
+                path.display()
+            );
+        }
+
+        let input = self.download(day)?;
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(&path, &input).with_context(|| format!("Can't write {}", path.display()))?;
+        Ok(input)
+    }
+
+    fn examples(&self, day: u16) -> Vec<String> {
+        self.cache.examples(day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread::JoinHandle,
+    };
+
+    use super::*;
+
+    /// Serves a single request with `status` and `body`, handing back the request's head.
+    fn serve_once(status: &str, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let status = status.to_string();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            while !head.ends_with("\r\n\r\n") {
+                reader.read_line(&mut head).unwrap();
+            }
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            head
+        });
+        (url, server)
+    }
+
+    fn provider(base_url: String, cache: &tempfile::TempDir) -> HttpProvider {
+        HttpProvider {
+            base_url,
+            session: "s3cret".to_string(),
+            cache: FsProvider::new(cache.path().join("inputs")),
+        }
+    }
+
+    #[test]
+    fn test_fs_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("07e.txt"), "example").unwrap();
+        let fs = FsProvider::new(dir.path());
+        assert_eq!(fs.input(7, &InputKind::example()).unwrap(), "example");
+        assert!(fs.input(7, &InputKind::Real).is_err());
+    }
+
+    #[test]
+    fn test_named_examples() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("07e.txt"), "first").unwrap();
+        std::fs::write(dir.path().join("07e2.txt"), "second").unwrap();
+        std::fs::write(dir.path().join("17e.txt"), "other day").unwrap();
+        std::fs::create_dir(dir.path().join("07e")).unwrap();
+        std::fs::write(dir.path().join("07e/quine.txt"), "third").unwrap();
+        let fs = FsProvider::new(dir.path());
+
+        assert_eq!(fs.examples(7), ["e", "e2", "quine"]);
+        let example = |name: &str| InputKind::Example(name.to_string());
+        assert_eq!(fs.input(7, &example("e2")).unwrap(), "second");
+        assert_eq!(fs.input(7, &example("quine")).unwrap(), "third");
+        assert!(fs.input(7, &example("e3")).is_err());
+        assert_eq!(example("quine").to_string(), "example quine");
+        assert_eq!(InputKind::example().to_string(), "example");
+    }
+
+    #[test]
+    fn test_fs_provider_search_order() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        std::fs::write(first.path().join("01.txt"), "first").unwrap();
+        std::fs::write(second.path().join("01.txt"), "second").unwrap();
+        std::fs::write(second.path().join("02.txt"), "second").unwrap();
+        let fs = FsProvider {
+            dirs: vec![first.path().into(), second.path().into()],
+        };
+        assert_eq!(fs.input(1, &InputKind::Real).unwrap(), "first");
+        assert_eq!(fs.input(2, &InputKind::Real).unwrap(), "second");
+
+        let error = fs.input(3, &InputKind::Real).unwrap_err();
+        let not_found = error.downcast_ref::<InputNotFound>().unwrap();
+        assert_eq!(
+            not_found.tried,
+            [first.path().join("03.txt"), second.path().join("03.txt")]
+        );
+        assert!(error
+            .to_string()
+            .contains(&second.path().display().to_string()));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let fs = FsProvider::resolve(Some(Path::new("/tmp/puzzles")));
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        assert_eq!(fs.dirs.last(), Some(&manifest));
+        assert!(fs.dirs.contains(&PathBuf::from("/tmp/puzzles")));
+        assert!(fs.input(1, &InputKind::example()).is_ok());
+    }
+
+    #[test]
+    fn test_http_provider_caches() {
+        let cache = tempfile::tempdir().unwrap();
+        let (url, server) = serve_once("200 OK", "1 2 3\n");
+        let http = provider(url, &cache);
+
+        assert_eq!(http.input(5, &InputKind::Real).unwrap(), "1 2 3\n");
+        let head = server.join().unwrap().to_lowercase();
+        assert!(head.starts_with("get /2024/day/5/input "));
+        assert!(head.contains("cookie: session=s3cret"));
+
+        // The server is gone, so this can only come from the cache.
+        assert_eq!(http.input(5, &InputKind::Real).unwrap(), "1 2 3\n");
+        assert!(http.input(5, &InputKind::example()).is_err());
+    }
+
+    #[test]
+    fn test_http_provider_errors() {
+        let cache = tempfile::tempdir().unwrap();
+        let (url, server) = serve_once("400 Bad Request", "Please log in");
+        let http = provider(url, &cache);
+
+        assert!(http.input(5, &InputKind::Real).is_err());
+        server.join().unwrap();
+        assert!(!cache.path().join("inputs/05.txt").exists());
+    }
+}
@@ -0,0 +1,56 @@
+//! Advent of Code 2024 solutions.
+//!
+//! Every day implements [`solution::Solution`] and is registered in [`registry`], so other tools
+//! can solve any day and get the answers back as values.
+
+pub mod animate;
+pub mod bench;
+pub mod fuzz;
+pub mod input;
+pub mod params;
+pub mod parse;
+pub mod render;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod utils;
+pub mod verify;
+
+solution::days! {
+    day01,
+    day02,
+    day03,
+    day04,
+    day05,
+    day06,
+    day07,
+    day08,
+    day09,
+    day10,
+    day11,
+    day12,
+    day13,
+    day14,
+    day15,
+    day16,
+    day17,
+    day18,
+    day19,
+    day20,
+    day21,
+    day22,
+    day23,
+    day24,
+    day25,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_order() {
+        let days = registry().iter().map(|p| p.day()).collect::<Vec<_>>();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    }
+}
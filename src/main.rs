@@ -1,66 +1,321 @@
-use anyhow::anyhow;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod utils;
-mod day22;
-mod day23;
-mod day24;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use clap::Parser;
+
+use aoc2024::{
+    animate::{Animation, Quit},
+    bench, find,
+    input::{FsProvider, InputKind, InputProvider, StdinProvider},
+    registry, runner, scaffold,
+    solution::{Params, Puzzle},
+    utils::parallel,
+    verify::{self, AnswerDirs, Expected},
+};
+use cli::{input_kind, AnimateArgs, Cli, Command, Format, FrameArgs, InputArgs};
+
+mod cli;
 
 fn main() -> anyhow::Result<()> {
-    let day: u16 = std::env::args()
-        .nth(1)
-        .ok_or(anyhow!("Missing day"))?
-        .parse()?;
-
-    let example = std::env::args().nth(2).unwrap_or_default() == "e";
-
-    match day {
-        1 => day01::run(example)?,
-        2 => day02::run(example)?,
-        3 => day03::run(example)?,
-        4 => day04::run(example)?,
-        5 => day05::run(example)?,
-        6 => day06::run(example)?,
-        7 => day07::run(example)?,
-        8 => day08::run(example)?,
-        9 => day09::run(example)?,
-        10 => day10::run(example)?,
-        11 => day11::run(example)?,
-        12 => day12::run(example)?,
-        13 => day13::run(example)?,
-        14 => day14::run(example)?,
-        15 => day15::run(example)?,
-        16 => day16::run(example)?,
-        17 => day17::run(example)?,
-        18 => day18::run(example)?,
-        19 => day19::run(example)?,
-        20 => day20::run(example)?,
-        21 => day21::run(example)?,
-        22 => day22::run(example)?,
-        23 => day23::run(example)?,
-        24 => day24::run(example)?,
-        _ => return Err(anyhow!("Unknown day")),
+    let cli = Cli::parse();
+    let input_dir = cli.input_dir.as_deref();
+    let answers = AnswerDirs::resolve(input_dir);
+
+    match cli.command {
+        Command::Run {
+            day,
+            input,
+            params,
+            part,
+            format,
+            animate,
+        } => run(
+            day,
+            &input,
+            input_dir,
+            &params.params(),
+            part,
+            format,
+            &animate,
+        ),
+        Command::All {
+            days,
+            example,
+            download,
+            params,
+            part,
+            format,
+        } => all(
+            &days,
+            &input_kind(example),
+            &*download.provider(input_dir)?,
+            &answers,
+            &params.params(),
+            part,
+            format,
+        ),
+        Command::Verify { days } => verify(&days, &FsProvider::resolve(input_dir), &answers),
+        Command::Bench {
+            days,
+            example,
+            download,
+            params,
+            options,
+        } => bench(
+            &days,
+            &input_kind(example),
+            &*download.provider(input_dir)?,
+            &answers,
+            &params.params(),
+            options.into(),
+        ),
+        Command::Render {
+            day,
+            input,
+            params,
+            frames,
+        } => render(day, &input, input_dir, &params.params(), &frames),
+        Command::Gen {
+            day,
+            seed,
+            size,
+            output,
+        } => generate(day, seed, size as usize, output.as_deref()),
+        Command::New { day } => new(day),
+    }
+}
+
+fn parts(part: Option<u8>) -> Vec<u8> {
+    match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
     }
+}
+
+fn puzzles(days: &str) -> anyhow::Result<Vec<&'static dyn Puzzle>> {
+    let selected = runner::parse_days(days)?;
+    let puzzles = registry()
+        .into_iter()
+        .filter(|puzzle| selected.contains(&puzzle.day()))
+        .collect::<Vec<_>>();
+    if puzzles.is_empty() {
+        return Err(anyhow!("None of days {days} are solved yet"));
+    }
+    Ok(puzzles)
+}
+
+/// The parameters for one of a day's inputs: the ones stored with its answers, overridden by
+/// `overrides`.
+fn params(
+    puzzle: &dyn Puzzle,
+    kind: &InputKind,
+    answers: &AnswerDirs,
+    overrides: &Params,
+) -> anyhow::Result<Params> {
+    let answers = answers.load(puzzle.day())?.unwrap_or_default();
+    let stored = answers.expected(kind).map(Expected::params);
+    Ok(stored.unwrap_or_default().merge(overrides))
+}
+
+fn read_input(day: u16, args: &InputArgs, input_dir: Option<&Path>) -> anyhow::Result<String> {
+    if args.stdin {
+        StdinProvider.input(day, &input_kind(args.example.clone()))
+    } else if let Some(path) = &args.input {
+        std::fs::read_to_string(path).with_context(|| format!("Can't read {}", path.display()))
+    } else {
+        let kind = input_kind(args.example.clone());
+        args.download.provider(input_dir)?.input(day, &kind)
+    }
+}
+
+/// Which kind of input `read_input` reads, for looking up its parameters. A file given with
+/// `--input` counts as a real input.
+fn read_kind(args: &InputArgs) -> InputKind {
+    match args.input {
+        Some(_) => InputKind::Real,
+        None => input_kind(args.example.clone()),
+    }
+}
+
+fn run(
+    day: u16,
+    input: &InputArgs,
+    input_dir: Option<&Path>,
+    overrides: &Params,
+    part: Option<u8>,
+    format: Format,
+    animate: &AnimateArgs,
+) -> anyhow::Result<()> {
+    let puzzle = find(day).ok_or(anyhow!("Day {day} isn't solved yet"))?;
+    if let Some(part) = part.filter(|&part| part > puzzle.parts()) {
+        return Err(anyhow!("Day {day} has no part {part}"));
+    }
+    let data = read_input(day, input, input_dir)?;
+    let answers = AnswerDirs::resolve(input_dir);
+    let params = params(puzzle, &read_kind(input), &answers, overrides)?;
+    if animate.animate {
+        let played = Animation::stdout(animate.fps)
+            .and_then(|mut animation| puzzle.parse(&data, &params)?.render(&mut animation));
+        // Quitting part of the way through still goes on to solve the day.
+        match played {
+            Err(e) if !e.is::<Quit>() => return Err(e),
+            _ => {}
+        }
+    }
+    let run = runner::run(puzzle, &data, &params, &parts(part))?;
+
+    match format {
+        Format::Text => {
+            println!("Day {day}");
+            for part in &run.parts {
+                match &part.answer {
+                    Ok(answer) => println!("Part {}: {answer}", part.part),
+                    Err(e) => println!("Part {}: error: {e}", part.part),
+                }
+            }
+        }
+        Format::Json | Format::Jsonl => {
+            runner::write_records(&run.records(), format == Format::Jsonl, std::io::stdout())?
+        }
+    }
+
+    Ok(())
+}
 
+fn render(
+    day: u16,
+    input: &InputArgs,
+    input_dir: Option<&Path>,
+    overrides: &Params,
+    args: &FrameArgs,
+) -> anyhow::Result<()> {
+    let puzzle = find(day).ok_or(anyhow!("Day {day} isn't solved yet"))?;
+    let data = read_input(day, input, input_dir)?;
+    let answers = AnswerDirs::resolve(input_dir);
+    let params = params(puzzle, &read_kind(input), &answers, overrides)?;
+    let mut frames = args.writer()?;
+    puzzle.parse(&data, &params)?.render(&mut frames)?;
+    println!(
+        "Wrote {} frames to {}",
+        frames.written().len(),
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn generate(day: u16, seed: u64, size: usize, output: Option<&Path>) -> anyhow::Result<()> {
+    let puzzle = find(day).ok_or(anyhow!("Day {day} isn't solved yet"))?;
+    let input = puzzle.generate(seed, size)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, input).with_context(|| format!("Can't write {}", path.display()))
+        }
+        None => {
+            print!("{input}");
+            Ok(())
+        }
+    }
+}
+
+fn all(
+    days: &str,
+    kind: &InputKind,
+    inputs: &dyn InputProvider,
+    answers: &AnswerDirs,
+    overrides: &Params,
+    part: Option<u8>,
+    format: Format,
+) -> anyhow::Result<()> {
+    // With the `parallel` feature the days run at the same time, so their timings include some
+    // contention, but the table still comes out in day order.
+    let puzzles = puzzles(days)?;
+    overrides.check_known(puzzles.iter().flat_map(|puzzle| puzzle.params()))?;
+    let runs = parallel::map(&puzzles, |&puzzle| {
+        let overrides = overrides.only(puzzle.params());
+        let run = inputs.input(puzzle.day(), kind).and_then(|data| {
+            let params = params(puzzle, kind, answers, &overrides)?;
+            runner::run(puzzle, &data, &params, &parts(part))
+        });
+        (puzzle.day(), run)
+    });
+
+    match format {
+        Format::Text => runner::print_table(&runs),
+        Format::Json | Format::Jsonl => {
+            let records = runs
+                .iter()
+                .flat_map(|(day, run)| match run {
+                    Ok(run) => run.records(),
+                    Err(e) => vec![runner::Record::failed(*day, e)],
+                })
+                .collect::<Vec<_>>();
+            runner::write_records(&records, format == Format::Jsonl, std::io::stdout())?
+        }
+    }
+
+    let failed = runs.iter().filter(|(_, run)| run.is_err()).count();
+    if failed > 0 {
+        return Err(anyhow!("{failed} of {} days couldn't be run", runs.len()));
+    }
+    Ok(())
+}
+
+fn verify(days: &str, inputs: &dyn InputProvider, answers: &AnswerDirs) -> anyhow::Result<()> {
+    let checks = verify::verify(&puzzles(days)?, inputs, answers)?;
+    verify::print_table(&checks);
+    if !checks.iter().all(verify::Check::is_ok) {
+        return Err(anyhow!("Some answers didn't match"));
+    }
+    Ok(())
+}
+
+fn bench(
+    days: &str,
+    kind: &InputKind,
+    inputs: &dyn InputProvider,
+    answers: &AnswerDirs,
+    overrides: &Params,
+    options: bench::BenchOptions,
+) -> anyhow::Result<()> {
+    let mut baseline = bench::Baseline::load(&options.baseline)?;
+    let mut regressed = false;
+    let puzzles = puzzles(days)?;
+    overrides.check_known(puzzles.iter().flat_map(|puzzle| puzzle.params()))?;
+    for puzzle in puzzles {
+        // Like the `all` table, a day that can't be run is reported and the rest still are.
+        let result = inputs.input(puzzle.day(), kind).and_then(|data| {
+            let params = params(puzzle, kind, answers, &overrides.only(puzzle.params()))?;
+            bench::bench(puzzle, &data, &params, &options)
+        });
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Day {}: error: {e}\n", puzzle.day());
+                continue;
+            }
+        };
+        let old = baseline.get(puzzle.day(), kind);
+        regressed |= bench::report(puzzle.day(), &result, old, &options);
+        baseline.insert(puzzle.day(), kind, result);
+        println!();
+    }
+
+    if options.save {
+        baseline.save(&options.baseline)?;
+        println!("Saved baseline to {}", options.baseline);
+    }
+
+    if regressed {
+        return Err(anyhow!("Performance regressed"));
+    }
+    Ok(())
+}
+
+fn new(day: u16) -> anyhow::Result<()> {
+    let changed = scaffold::new_day(&scaffold::crate_root()?, day)?;
+    println!("Added day {day} to the project:");
+    for path in changed {
+        println!("- {}", path.display());
+    }
     Ok(())
 }
@@ -0,0 +1,199 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail};
+
+/// A value that a day reads at runtime instead of hard-coding, such as the size of its grid, which
+/// is usually different for the examples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub help: &'static str,
+}
+
+/// Parameter values by name, kept as text until a day asks for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn get<T>(&self, name: &str) -> anyhow::Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self
+            .0
+            .get(name)
+            .ok_or_else(|| anyhow!("Parameter `{name}` isn't set"))?;
+        value
+            .parse()
+            .map_err(|e| anyhow!("Invalid value {value:?} for parameter `{name}`: {e}"))
+    }
+
+    /// The declared parameters' defaults, overridden by these values. Values for parameters that
+    /// weren't declared are an error.
+    pub fn resolve(&self, declared: &[Param]) -> anyhow::Result<Params> {
+        if let Some(name) = self
+            .0
+            .keys()
+            .find(|k| !declared.iter().any(|p| p.name == *k))
+        {
+            if declared.is_empty() {
+                bail!("Unknown parameter `{name}`, this day doesn't take any");
+            }
+            let known = declared
+                .iter()
+                .map(|p| format!("\n  {}: {} (default {})", p.name, p.help, p.default))
+                .collect::<String>();
+            bail!("Unknown parameter `{name}`, expected one of:{known}");
+        }
+
+        let mut params = declared
+            .iter()
+            .map(|p| (p.name.to_string(), p.default.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        params.extend(self.0.clone());
+        Ok(Params(params))
+    }
+
+    /// Checks that every value is for one of the `declared` parameters, which can come from several
+    /// days. Goes with [`Params::only`], so that a misspelled name isn't quietly dropped.
+    pub fn check_known<'a>(
+        &self,
+        declared: impl IntoIterator<Item = &'a Param>,
+    ) -> anyhow::Result<()> {
+        let known = declared
+            .into_iter()
+            .map(|p| p.name)
+            .collect::<BTreeSet<_>>();
+        if let Some(name) = self.0.keys().find(|k| !known.contains(k.as_str())) {
+            if known.is_empty() {
+                bail!("Unknown parameter `{name}`, none of these days take any");
+            }
+            let known = known.into_iter().collect::<Vec<_>>().join(", ");
+            bail!("Unknown parameter `{name}`, these days take: {known}");
+        }
+        Ok(())
+    }
+
+    /// Just the values for the declared parameters, for applying one set of values to several days.
+    pub fn only(&self, declared: &[Param]) -> Params {
+        Params(
+            self.0
+                .iter()
+                .filter(|(k, _)| declared.iter().any(|p| p.name == *k))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        )
+    }
+
+    /// These values, overridden by `other`'s.
+    pub fn merge(&self, other: &Params) -> Params {
+        let mut params = self.clone();
+        params.0.extend(other.0.clone());
+        params
+    }
+}
+
+impl FromIterator<(String, String)> for Params {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        Params(iter.into_iter().collect())
+    }
+}
+
+/// Parses a `name=value` pair, as given to `--param`.
+pub fn parse_assignment(arg: &str) -> anyhow::Result<(String, String)> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => bail!("Expected a parameter as name=value, got {arg:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DECLARED: &[Param] = &[
+        Param {
+            name: "width",
+            default: "101",
+            help: "",
+        },
+        Param {
+            name: "height",
+            default: "103",
+            help: "",
+        },
+    ];
+
+    #[test]
+    fn test_resolve() {
+        let mut overrides = Params::new();
+        overrides.set("width", "11");
+        let params = overrides.resolve(DECLARED).unwrap();
+        assert_eq!(params.get::<usize>("width").unwrap(), 11);
+        assert_eq!(params.get::<usize>("height").unwrap(), 103);
+        assert!(params.get::<usize>("depth").is_err());
+
+        overrides.set("depth", "3");
+        let error = overrides.resolve(DECLARED).unwrap_err().to_string();
+        assert!(error.contains("`depth`"), "{error}");
+        assert!(error.contains("\n  height:  (default 103)"), "{error}");
+        assert_eq!(overrides.only(DECLARED).iter().count(), 1);
+
+        overrides.set("width", "wide");
+        let params = overrides.only(DECLARED).resolve(DECLARED).unwrap();
+        assert!(params.get::<usize>("width").is_err());
+    }
+
+    #[test]
+    fn test_check_known() {
+        let mut overrides = Params::new();
+        overrides.set("width", "11");
+        assert!(overrides.check_known(DECLARED).is_ok());
+        assert!(overrides
+            .check_known(DECLARED.iter().chain(DECLARED))
+            .is_ok());
+
+        overrides.set("widht", "11");
+        let error = overrides.check_known(DECLARED).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Unknown parameter `widht`, these days take: height, width"
+        );
+        let error = overrides.check_known([]).unwrap_err().to_string();
+        assert!(error.contains("none of these days"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            parse_assignment("width = 11").unwrap(),
+            ("width".to_string(), "11".to_string())
+        );
+        assert!(parse_assignment("width").is_err());
+        assert!(parse_assignment("=11").is_err());
+    }
+}
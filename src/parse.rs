@@ -0,0 +1,231 @@
+use std::{fmt::Display, str::FromStr};
+
+use regex::{Captures, Regex};
+
+/// A puzzle input that couldn't be parsed. Lines and columns start at 1, and `text` is the part of
+/// the input that was rejected (empty when the input ended too early).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if self.text.is_empty() {
+            write!(f, " (found end of input)")
+        } else {
+            write!(f, " (found {:?})", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A slice of the puzzle input that remembers where it came from, so that errors can point at it.
+#[derive(Debug, Clone, Copy)]
+pub struct Span<'a> {
+    source: &'a str,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(source: &'a str) -> Span<'a> {
+        Span {
+            source,
+            start: 0,
+            end: source.len(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        &self.source[self.start..self.end]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Wraps a slice of this span's text.
+    fn sub(&self, text: &'a str) -> Span<'a> {
+        let start = text.as_ptr() as usize - self.source.as_ptr() as usize;
+        Span {
+            source: self.source,
+            start,
+            end: start + text.len(),
+        }
+    }
+
+    /// An empty span just past the end of this one, for reporting input that stops too early.
+    pub fn end(&self) -> Span<'a> {
+        Span {
+            source: self.source,
+            start: self.end,
+            end: self.end,
+        }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        self.as_str().lines().map(move |line| span.sub(line))
+    }
+
+    pub fn trim(&self) -> Span<'a> {
+        self.sub(self.as_str().trim())
+    }
+
+    pub fn split(&self, delimiter: &'a str) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        self.as_str()
+            .split(delimiter)
+            .map(move |part| span.sub(part))
+    }
+
+    pub fn split_whitespace(&self) -> impl Iterator<Item = Span<'a>> {
+        let span = *self;
+        self.as_str()
+            .split_whitespace()
+            .map(move |part| span.sub(part))
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        match self.as_str().split_once(delimiter) {
+            Some((left, right)) => Ok((self.sub(left), self.sub(right))),
+            None => Err(self.error(format!("expected {delimiter:?}"))),
+        }
+    }
+
+    /// Every character along with a span covering just that character.
+    pub fn chars(&self) -> impl Iterator<Item = (Span<'a>, char)> {
+        let span = *self;
+        let text = self.as_str();
+        text.char_indices()
+            .map(move |(i, ch)| (span.sub(&text[i..i + ch.len_utf8()]), ch))
+    }
+
+    pub fn parse<T>(&self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.as_str().parse().map_err(|e| {
+            let name = std::any::type_name::<T>();
+            let name = name.rsplit("::").next().unwrap_or(name);
+            self.error(format!("expected {name} ({e})"))
+        })
+    }
+
+    /// Everything after the first `n` bytes.
+    pub fn skip(&self, n: usize) -> Span<'a> {
+        self.sub(&self.as_str()[n..])
+    }
+
+    pub fn captures(&self, regex: &Regex) -> Option<Groups<'a>> {
+        regex.captures(self.as_str()).map(|captures| Groups {
+            span: *self,
+            captures,
+        })
+    }
+
+    /// Like [`Span::captures`], but a missing match is an error.
+    pub fn matched(&self, regex: &Regex) -> Result<Groups<'a>, ParseError> {
+        self.captures(regex)
+            .ok_or_else(|| self.error(format!("expected a match for `{regex}`")))
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let before = &self.source[..self.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: self.as_str().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// The groups of a regex match, as spans of the input.
+pub struct Groups<'a> {
+    span: Span<'a>,
+    captures: Captures<'a>,
+}
+
+impl<'a> Groups<'a> {
+    pub fn get(&self, group: usize) -> Option<Span<'a>> {
+        self.captures
+            .get(group)
+            .map(|m| self.span.sub(&self.span.as_str()[m.range()]))
+    }
+
+    /// Parses a group that's expected to have matched.
+    pub fn parse<T>(&self, group: usize) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.get(group) {
+            Some(span) => span.parse(),
+            None => Err(self.span.error(format!("expected group {group} to match"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let input = Span::new("1 2\n3 x4\n");
+        let token = input
+            .lines()
+            .nth(1)
+            .unwrap()
+            .split_whitespace()
+            .nth(1)
+            .unwrap();
+        let error = token.parse::<u32>().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 3);
+        assert_eq!(error.text, "x4");
+        assert_eq!(
+            error.to_string(),
+            r#"line 2, column 3: expected u32 (invalid digit found in string) (found "x4")"#
+        );
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let input = Span::new("ab\ncd");
+        let error = input.end().error("expected another line");
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected another line (found end of input)"
+        );
+    }
+
+    #[test]
+    fn test_split_once_and_captures() {
+        let input = Span::new("p=1,2\nq=3;4");
+        let line = input.lines().nth(1).unwrap();
+        let error = line.split_once(",").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.text, "q=3;4");
+
+        let regex = Regex::new(r"(\w)=(\d+);(\d+)").unwrap();
+        let groups = line.matched(&regex).unwrap();
+        assert_eq!(groups.parse::<u8>(3), Ok(4));
+        assert_eq!(groups.get(2).unwrap().error("").column, 3);
+        assert!(input.lines().next().unwrap().matched(&regex).is_err());
+    }
+}
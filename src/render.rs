@@ -0,0 +1,343 @@
+//! Turns grids into images, so that a day's state can be looked at in an image viewer instead of
+//! as a wall of text. Images are written as PPM, which needs no dependencies, or as PNG with the
+//! `png` feature.
+
+use std::{
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::utils::{Grid, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(96, 96, 96);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(133, 153, 0);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(240, 200, 0);
+    pub const ORANGE: Rgb = Rgb(203, 75, 22);
+
+    /// The colour `t` of the way from `self` to `other`, where `t` is between 0 and 1.
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+}
+
+/// An RGB image stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn filled(width: usize, height: usize, color: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// One pixel per cell, coloured by `color`.
+    pub fn from_grid<T>(grid: &Grid<T>, mut color: impl FnMut(Vec2, &T) -> Rgb) -> Image {
+        Image {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(pos, cell)| color(pos, cell)).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<Rgb> {
+        self.index(pos).map(|i| self.pixels[i])
+    }
+
+    /// Colours a single pixel. Positions outside the image are ignored, so markers can be drawn
+    /// without checking them first.
+    pub fn set(&mut self, pos: Vec2, color: Rgb) {
+        if let Some(i) = self.index(pos) {
+            self.pixels[i] = color;
+        }
+    }
+
+    /// Every row of the image, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[Rgb]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    /// The image with every pixel blown up to a `factor` by `factor` square, as most puzzle grids
+    /// are too small to see at one pixel per cell.
+    pub fn scaled(&self, factor: usize) -> Image {
+        let factor = factor.max(1);
+        let pixels = self
+            .rows()
+            .flat_map(|row| {
+                let row = row
+                    .iter()
+                    .flat_map(|&color| std::iter::repeat_n(color, factor))
+                    .collect::<Vec<_>>();
+                std::iter::repeat_n(row, factor).flatten()
+            })
+            .collect();
+        Image {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    /// Writes the image as a binary PPM (`P6`).
+    pub fn write_ppm(&self, mut w: impl Write) -> std::io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.bytes())?;
+        w.flush()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, w: impl Write) -> anyhow::Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.bytes())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn write(&self, w: impl Write, format: ImageFormat) -> anyhow::Result<()> {
+        match format {
+            ImageFormat::Ppm => Ok(self.write_ppm(w)?),
+            #[cfg(feature = "png")]
+            ImageFormat::Png => self.write_png(w),
+            #[cfg(not(feature = "png"))]
+            ImageFormat::Png => bail!("Writing PNG needs the `png` feature"),
+        }
+    }
+
+    /// Writes the image to `path` in the format given by its extension.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let format = ImageFormat::from_path(path)?;
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Can't create {}", path.display()))?;
+        self.write(std::io::BufWriter::new(file), format)
+    }
+
+    fn index(&self, pos: Vec2) -> Option<usize> {
+        let (x, y) = pos.to_usize()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b])
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFormat {
+    #[default]
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> anyhow::Result<ImageFormat> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .ok_or_else(|| anyhow!("{} has no image extension", path.display()))?
+            .parse()
+    }
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "png" => Ok(ImageFormat::Png),
+            _ => bail!("Unknown image format {s:?}, expected ppm or png"),
+        }
+    }
+}
+
+/// Receives the frames a day draws, one at a time.
+pub trait FrameSink {
+    fn frame(&mut self, image: &Image) -> anyhow::Result<()>;
+}
+
+/// Writes frames to numbered files in a directory: `frame00000000.ppm`, `frame00000001.ppm` and
+/// so on, padded so that sorting them by name keeps them in order up to a hundred million frames.
+/// Long simulations can keep only every nth frame.
+#[derive(Debug, Clone)]
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: ImageFormat,
+    scale: usize,
+    every: usize,
+    seen: usize,
+    written: Vec<PathBuf>,
+}
+
+impl FrameWriter {
+    /// Writes into `dir`, which is created if it's missing.
+    pub fn new(dir: impl Into<PathBuf>, format: ImageFormat) -> anyhow::Result<FrameWriter> {
+        if cfg!(not(feature = "png")) && format == ImageFormat::Png {
+            bail!("Writing PNG needs the `png` feature");
+        }
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).with_context(|| format!("Can't create {}", dir.display()))?;
+        Ok(FrameWriter {
+            dir,
+            format,
+            scale: 1,
+            every: 1,
+            seen: 0,
+            written: Vec::new(),
+        })
+    }
+
+    /// Blows every frame up by `scale`, see [`Image::scaled`].
+    pub fn scale(mut self, scale: usize) -> FrameWriter {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Only writes every `every`th frame, starting with the first.
+    pub fn every(mut self, every: usize) -> FrameWriter {
+        self.every = every.max(1);
+        self
+    }
+
+    /// The files written so far.
+    pub fn written(&self) -> &[PathBuf] {
+        &self.written
+    }
+}
+
+impl FrameSink for FrameWriter {
+    fn frame(&mut self, image: &Image) -> anyhow::Result<()> {
+        let n = self.seen;
+        self.seen += 1;
+        if !n.is_multiple_of(self.every) {
+            return Ok(());
+        }
+        let path = self
+            .dir
+            .join(format!("frame{n:08}.{}", self.format.extension()));
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Can't create {}", path.display()))?;
+        let file = std::io::BufWriter::new(file);
+        if self.scale > 1 {
+            image.scaled(self.scale).write(file, self.format)?;
+        } else {
+            image.write(file, self.format)?;
+        }
+        self.written.push(path);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        let grid = Grid::from_fn(2, 2, |pos| (pos.x + pos.y) % 2 == 0);
+        Image::from_grid(
+            &grid,
+            |_, &white| if white { Rgb::WHITE } else { Rgb::BLACK },
+        )
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut out = Vec::new();
+        checkerboard().write_ppm(&mut out).unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([255, 255, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_scaled() {
+        let image = checkerboard().scaled(2);
+        assert_eq!((image.width(), image.height()), (4, 4));
+        assert_eq!(image.get(Vec2 { x: 1, y: 1 }), Some(Rgb::WHITE));
+        assert_eq!(image.get(Vec2 { x: 2, y: 1 }), Some(Rgb::BLACK));
+        assert_eq!(image.get(Vec2 { x: 3, y: 3 }), Some(Rgb::WHITE));
+        assert_eq!(image.get(Vec2 { x: 4, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_frame_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut frames = FrameWriter::new(dir.path().join("frames"), ImageFormat::Ppm)
+            .unwrap()
+            .every(2);
+        for _ in 0..5 {
+            frames.frame(&checkerboard()).unwrap();
+        }
+        let names = frames
+            .written()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "frame00000000.ppm",
+                "frame00000002.ppm",
+                "frame00000004.ppm"
+            ]
+        );
+        assert!(dir.path().join("frames/frame00000004.ppm").exists());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        let mut out = Vec::new();
+        checkerboard().write_png(&mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG"));
+    }
+}
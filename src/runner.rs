@@ -0,0 +1,297 @@
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::solution::{Answer, Params, Puzzle};
+
+/// The answers and timings of a single day.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u16,
+    pub input_hash: u64,
+    pub parse: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: u8,
+    pub elapsed: Duration,
+    pub answer: anyhow::Result<Answer>,
+}
+
+impl Run {
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts.iter().map(|p| p.elapsed).sum::<Duration>()
+    }
+
+    pub fn part(&self, part: u8) -> Option<&PartRun> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// Parses the input and solves the requested parts, timing each step separately. Parts the day
+/// doesn't have are left out.
+pub fn run(puzzle: &dyn Puzzle, data: &str, params: &Params, parts: &[u8]) -> anyhow::Result<Run> {
+    let (parsed, parse) = timed(|| puzzle.parse(data, params));
+    let parsed = parsed?;
+    let parts = parts
+        .iter()
+        .filter(|&&part| part <= puzzle.parts())
+        .map(|&part| {
+            let (answer, elapsed) = timed(|| match part {
+                1 => parsed.part1(),
+                2 => parsed.part2(),
+                _ => Err(anyhow!("There is no part {part}")),
+            });
+            PartRun {
+                part,
+                elapsed,
+                answer,
+            }
+        })
+        .collect();
+
+    Ok(Run {
+        day: puzzle.day(),
+        input_hash: input_hash(data),
+        parse,
+        parts,
+    })
+}
+
+/// Parses a selection of days: `all`, a single day, or a range such as `1..=12` or `1..13`.
+/// Days that aren't registered are skipped by the caller.
+pub fn parse_days(arg: &str) -> anyhow::Result<Vec<u16>> {
+    if arg == "all" {
+        return Ok((1..=25).collect());
+    }
+
+    let Some((start, end)) = arg.split_once("..") else {
+        let day = arg.parse()?;
+        if !(1..=25).contains(&day) {
+            return Err(anyhow!("Days go from 1 to 25, not {day}"));
+        }
+        return Ok(vec![day]);
+    };
+
+    let start: u16 = if start.is_empty() { 1 } else { start.parse()? };
+    let days = match end.strip_prefix('=') {
+        Some(end) => start..=end.parse()?,
+        None if end.is_empty() => start..=25,
+        None => start..=end.parse::<u16>()?.saturating_sub(1),
+    };
+
+    if days.is_empty() {
+        return Err(anyhow!("Empty range of days: {arg}"));
+    }
+    if !(1..=25).contains(days.start()) || !(1..=25).contains(days.end()) {
+        return Err(anyhow!("Days go from 1 to 25, not {arg}"));
+    }
+
+    Ok(days.collect())
+}
+
+/// One solved part, in the shape used for JSON output. A day that couldn't be run at all, because
+/// its input is missing or didn't parse, gets a single record with only the day and the error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub day: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_hash: Option<String>,
+}
+
+impl Record {
+    pub fn failed(day: u16, error: &anyhow::Error) -> Record {
+        Record {
+            day,
+            part: None,
+            answer: None,
+            error: Some(error.to_string()),
+            duration_ns: None,
+            input_hash: None,
+        }
+    }
+}
+
+impl Run {
+    pub fn records(&self) -> Vec<Record> {
+        self.parts
+            .iter()
+            .map(|part| Record {
+                day: self.day,
+                part: Some(part.part),
+                answer: part.answer.as_ref().ok().cloned(),
+                error: part.answer.as_ref().err().map(ToString::to_string),
+                duration_ns: Some(part.elapsed.as_nanos() as u64),
+                input_hash: Some(format!("{:016x}", self.input_hash)),
+            })
+            .collect()
+    }
+}
+
+/// 64-bit FNV-1a, which is stable across platforms and releases, unlike `std`'s hashers.
+pub fn input_hash(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Writes records as a single JSON array, or as JSON Lines with one record per line.
+pub fn write_records(
+    records: &[Record],
+    lines: bool,
+    mut out: impl std::io::Write,
+) -> anyhow::Result<()> {
+    if lines {
+        for record in records {
+            serde_json::to_writer(&mut out, record)?;
+            writeln!(out)?;
+        }
+    } else {
+        serde_json::to_writer_pretty(&mut out, records)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn answer_cell(part: Option<&PartRun>) -> String {
+    match part.map(|p| &p.answer) {
+        Some(Ok(answer)) => answer.to_string(),
+        Some(Err(e)) => format!("error: {e}"),
+        None => String::from("-"),
+    }
+}
+
+fn elapsed_cell(part: Option<&PartRun>) -> String {
+    match part {
+        Some(part) => format!("{:.2?}", part.elapsed),
+        None => String::from("-"),
+    }
+}
+
+pub fn print_table(runs: &[(u16, anyhow::Result<Run>)]) {
+    println!(
+        "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}  {:<20}  {:<20}",
+        "Day", "Parse", "Part 1", "Part 2", "Total", "Answer 1", "Answer 2"
+    );
+
+    let mut total = Duration::ZERO;
+    for (day, run) in runs {
+        match run {
+            Ok(run) => {
+                total += run.total();
+                println!(
+                    "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}  {:<20}  {:<20}",
+                    run.day,
+                    format!("{:.2?}", run.parse),
+                    elapsed_cell(run.part(1)),
+                    elapsed_cell(run.part(2)),
+                    format!("{:.2?}", run.total()),
+                    answer_cell(run.part(1)),
+                    answer_cell(run.part(2)),
+                );
+            }
+            Err(e) => println!("{day:>3}  error: {e}"),
+        }
+    }
+
+    println!(
+        "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "All",
+        "",
+        "",
+        "",
+        format!("{total:.2?}")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("7").unwrap(), vec![7]);
+        assert_eq!(parse_days("1..=3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(parse_days("1..3").unwrap(), vec![1, 2]);
+        assert_eq!(parse_days("23..").unwrap(), vec![23, 24, 25]);
+        assert_eq!(parse_days("all").unwrap().len(), 25);
+        assert!(parse_days("5..=2").is_err());
+        assert!(parse_days("five").is_err());
+        assert!(parse_days("30").is_err());
+        assert!(parse_days("0..=3").is_err());
+        assert!(parse_days("20..=26").is_err());
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_eq!(input_hash("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_single_part_day() {
+        let puzzle = crate::find(25).unwrap();
+        let lock = "#####\n.....\n.....\n.....\n.....\n.....\n.....\n";
+        let run = run(puzzle, lock, &Params::new(), &[1, 2]).unwrap();
+        assert_eq!(run.parts.len(), 1);
+        assert_eq!(
+            run.part(1).unwrap().answer.as_ref().unwrap(),
+            &Answer::Num(0)
+        );
+        assert!(run.part(2).is_none());
+    }
+
+    #[test]
+    fn test_write_records() {
+        let run = Run {
+            day: 3,
+            input_hash: input_hash("a"),
+            parse: Duration::ZERO,
+            parts: vec![
+                PartRun {
+                    part: 1,
+                    elapsed: Duration::from_nanos(1500),
+                    answer: Ok(Answer::Num(161)),
+                },
+                PartRun {
+                    part: 2,
+                    elapsed: Duration::from_nanos(20),
+                    answer: Err(anyhow!("Oops")),
+                },
+            ],
+        };
+
+        let mut out = Vec::new();
+        let mut records = run.records();
+        records.push(Record::failed(4, &anyhow!("No input")));
+        write_records(&records, true, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                r#"{"day":3,"part":1,"answer":161,"duration_ns":1500,"input_hash":"af63dc4c8601ec8c"}"#,
+                "\n",
+                r#"{"day":3,"part":2,"error":"Oops","duration_ns":20,"input_hash":"af63dc4c8601ec8c"}"#,
+                "\n",
+                r#"{"day":4,"error":"No input"}"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+
+const TEMPLATE: &str = include_str!("../new_day.tmpl");
+
+const ANSWERS: &str = "\
+# Fill these in once the answers are known.
+# part1 =
+# part2 =
+
+[example]
+# part1 =
+# part2 =
+";
+
+/// The root of this crate's source, where `new` adds days whatever directory it's run from. It's
+/// where the crate was built, so it fails if the source has moved or been deleted since.
+pub fn crate_root() -> anyhow::Result<PathBuf> {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    if !root.join("Cargo.toml").is_file() || !root.join("src/lib.rs").is_file() {
+        bail!(
+            "Can't find the source this was built from at {}",
+            root.display()
+        );
+    }
+    Ok(root)
+}
+
+/// Adds a new day to the project rooted at `root`: the module from the template, its entry in
+/// the `days!` list in `src/lib.rs`, and empty input and answer files. Nothing is written if any
+/// of those files already exist. Returns every file that was created or changed.
+pub fn new_day(root: &Path, day: u16) -> anyhow::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("Day {day} isn't a day of Advent of Code");
+    }
+
+    let files = [
+        (
+            root.join(format!("src/day{day:02}/mod.rs")),
+            TEMPLATE.replace("%%DAY%%", &day.to_string()),
+        ),
+        (root.join(format!("inputs/{day:02}.txt")), String::new()),
+        (root.join(format!("inputs/{day:02}e.txt")), String::new()),
+        (root.join(format!("answers/{day:02}.toml")), ANSWERS.into()),
+    ];
+    let existing = files
+        .iter()
+        .filter(|(path, _)| path.exists())
+        .map(|(path, _)| path.display().to_string())
+        .collect::<Vec<_>>();
+    if !existing.is_empty() {
+        bail!("Refusing to overwrite {}", existing.join(", "));
+    }
+
+    let lib = root.join("src/lib.rs");
+    let source =
+        std::fs::read_to_string(&lib).with_context(|| format!("Can't read {}", lib.display()))?;
+    let source = register(&source, day)?;
+
+    for (path, contents) in &files {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, contents)
+            .with_context(|| format!("Can't write {}", path.display()))?;
+    }
+    std::fs::write(&lib, source).with_context(|| format!("Can't write {}", lib.display()))?;
+
+    let mut changed = files.map(|(path, _)| path).to_vec();
+    changed.insert(1, lib);
+    Ok(changed)
+}
+
+/// Adds `dayNN` to the `days!` list in the source of `lib.rs`, keeping the list in order.
+fn register(source: &str, day: u16) -> anyhow::Result<String> {
+    let missing = || anyhow!("Can't find the `solution::days!` list in lib.rs");
+    let open =
+        source.find("solution::days! {\n").ok_or_else(missing)? + "solution::days! {\n".len();
+    let close = open + source[open..].find("}").ok_or_else(missing)?;
+
+    let name = format!("day{day:02}");
+    let mut modules = source[open..close]
+        .split(',')
+        .map(str::trim)
+        .filter(|module| !module.is_empty())
+        .collect::<Vec<_>>();
+    if modules.contains(&name.as_str()) {
+        bail!("{name} is already registered");
+    }
+    modules.push(&name);
+    modules.sort();
+
+    let list = modules
+        .iter()
+        .map(|module| format!("    {module},\n"))
+        .collect::<String>();
+    Ok(format!("{}{list}{}", &source[..open], &source[close..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str =
+        "pub mod utils;\n\nsolution::days! {\n    day01,\n    day03,\n}\n\nfn x() {}\n";
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(LIB, 2).unwrap(),
+            "pub mod utils;\n\nsolution::days! {\n    day01,\n    day02,\n    day03,\n}\n\nfn x() {}\n"
+        );
+        assert!(register(LIB, 3).is_err());
+        assert!(register("fn x() {}\n", 2).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+        let changed = new_day(root, 2).unwrap();
+        assert_eq!(changed.len(), 5);
+        let module = std::fs::read_to_string(root.join("src/day02/mod.rs")).unwrap();
+        assert!(module.contains("const DAY: u16 = 2;"));
+        assert!(root.join("inputs/02e.txt").exists());
+        let answers = std::fs::read_to_string(root.join("answers/02.toml")).unwrap();
+        assert!(toml::from_str::<crate::verify::Answers>(&answers).is_ok());
+
+        // A second run must leave everything alone, including lib.rs.
+        std::fs::write(root.join("inputs/02.txt"), "puzzle").unwrap();
+        let lib = std::fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(new_day(root, 2).is_err());
+        assert_eq!(
+            std::fs::read_to_string(root.join("inputs/02.txt")).unwrap(),
+            "puzzle"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            lib
+        );
+
+        assert!(new_day(root, 26).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+pub use crate::params::{Param, Params};
+use crate::{render::FrameSink, utils::rng::Rng};
+
+/// The answer to one part of a puzzle, either a number or a piece of text (such as day 17's
+/// program output or day 23's password).
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Num(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Num(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! answer_from_num {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(value: $ty) -> Self {
+                    Answer::Num(value as i64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_num!(i64, isize, u64, usize, u32);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+/// A single day's puzzle. The input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+
+    const DAY: u16;
+
+    /// The parameters this day reads, such as grid sizes that differ between the real input and
+    /// the examples. Every one of them is set when [`Solution::parse`] is called.
+    const PARAMS: &'static [Param] = &[];
+
+    /// How many parts the puzzle has. Only the last day has just the one, and its
+    /// [`Solution::part2`] is never run.
+    const PARTS: u8 = 2;
+
+    fn parse(&self, input: &str, params: &Params) -> anyhow::Result<Self::Input>;
+
+    fn part1(&self, input: &Self::Input) -> anyhow::Result<Answer>;
+
+    fn part2(&self, input: &Self::Input) -> anyhow::Result<Answer>;
+
+    /// Draws the puzzle into `frames`: one frame for a day with a single picture worth looking at,
+    /// or one per step for days that simulate something.
+    fn render(&self, _input: &Self::Input, _frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        anyhow::bail!("Day {} has nothing to render", Self::DAY)
+    }
+
+    /// Makes up an input in the same format as the real one, about `size` times as big, for
+    /// testing and benchmarking at scale.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> anyhow::Result<String> {
+        anyhow::bail!("Day {} has no input generator", Self::DAY)
+    }
+}
+
+/// Type-erased version of [`Solution`] so that every day can live in the same registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u16;
+
+    fn params(&self) -> &'static [Param];
+
+    fn parts(&self) -> u8;
+
+    /// Parses the input, with `params` overriding the defaults of the day's parameters.
+    fn parse<'a>(&'a self, input: &str, params: &Params) -> anyhow::Result<Box<dyn Parsed + 'a>>;
+
+    /// Makes up an input from `seed`, see [`Solution::generate`].
+    fn generate(&self, seed: u64, size: usize) -> anyhow::Result<String>;
+}
+
+/// A parsed puzzle input, ready to be solved.
+pub trait Parsed {
+    fn part1(&self) -> anyhow::Result<Answer>;
+
+    fn part2(&self) -> anyhow::Result<Answer>;
+
+    fn render(&self, frames: &mut dyn FrameSink) -> anyhow::Result<()>;
+}
+
+struct ParsedInput<'a, S: Solution> {
+    solution: &'a S,
+    input: S::Input,
+}
+
+impl<S: Solution> Parsed for ParsedInput<'_, S> {
+    fn part1(&self) -> anyhow::Result<Answer> {
+        self.solution.part1(&self.input)
+    }
+
+    fn part2(&self) -> anyhow::Result<Answer> {
+        self.solution.part2(&self.input)
+    }
+
+    fn render(&self, frames: &mut dyn FrameSink) -> anyhow::Result<()> {
+        self.solution.render(&self.input, frames)
+    }
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u16 {
+        S::DAY
+    }
+
+    fn params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn parts(&self) -> u8 {
+        S::PARTS
+    }
+
+    fn parse<'a>(&'a self, input: &str, params: &Params) -> anyhow::Result<Box<dyn Parsed + 'a>> {
+        let params = params.resolve(S::PARAMS)?;
+        let input = Solution::parse(self, input, &params)?;
+        Ok(Box::new(ParsedInput {
+            solution: self,
+            input,
+        }))
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> anyhow::Result<String> {
+        Solution::generate(self, &mut Rng::new(seed), size.max(1))
+    }
+}
+
+/// Declares every day module and builds the registry from the same list, so adding a day only
+/// means adding its module name here.
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every registered day, in calendar order.
+        pub fn registry() -> Vec<&'static dyn $crate::solution::Puzzle> {
+            vec![$(&$module::Day),*]
+        }
+
+        /// Looks up a single day in the registry.
+        pub fn find(day: u16) -> Option<&'static dyn $crate::solution::Puzzle> {
+            registry().into_iter().find(|puzzle| puzzle.day() == day)
+        }
+    };
+}
+
+pub(crate) use days;
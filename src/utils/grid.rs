@@ -0,0 +1,322 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::parse::{ParseError, Span};
+
+use super::Vec2;
+
+/// A rectangular grid stored row by row in a single buffer. Positions outside the grid are never
+/// an error, they just have nothing in them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Vec2) -> T) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                f(Vec2 {
+                    x: x as isize,
+                    y: y as isize,
+                })
+            })
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses one row per line, turning each character into a cell with `f`. Characters that `f`
+    /// doesn't accept and rows of different lengths are errors.
+    pub fn parse(
+        input: &str,
+        mut f: impl FnMut(Vec2, char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (y, line) in Span::new(input).lines().enumerate() {
+            let row_start = cells.len();
+            for (x, (span, ch)) in line.chars().enumerate() {
+                let pos = Vec2 {
+                    x: x as isize,
+                    y: y as isize,
+                };
+                match f(pos, ch) {
+                    Some(cell) => cells.push(cell),
+                    None => return Err(span.error("unexpected character")),
+                }
+            }
+
+            let row_width = cells.len() - row_start;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(line.error(format!("expected a row of {width} cells")));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2 {
+            x: self.width as isize,
+            y: self.height as isize,
+        }
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        (0..self.width as isize).contains(&pos.x) && (0..self.height as isize).contains(&pos.y)
+    }
+
+    fn offset(&self, pos: Vec2) -> Option<usize> {
+        let (x, y) = pos.to_usize()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn position(&self, offset: usize) -> Vec2 {
+        Vec2 {
+            x: (offset % self.width) as isize,
+            y: (offset / self.width) as isize,
+        }
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Vec2 {
+            x: (i % width) as isize,
+            y: (i / width) as isize,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The up to four orthogonal neighbours of `pos` that are inside the grid.
+    pub fn neighbors(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        pos.neighbors().into_iter().filter(|&p| self.contains(p))
+    }
+
+    /// Like [`Grid::neighbors`], but including diagonals.
+    pub fn neighbors8(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        pos.neighbors8().into_iter().filter(|&p| self.contains(p))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` doesn't accept a size of 0, and an empty grid has no rows anyway.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        self.rows().nth(y)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(width.max(1))
+            .take(if x < width { self.height } else { 0 })
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Walks from `start` in steps of `step` until it leaves the grid.
+    pub fn ray(&self, start: Vec2, step: Vec2) -> impl Iterator<Item = (Vec2, &T)> {
+        std::iter::successors(Some(start), move |&pos| Some(pos + step))
+            .map_while(|pos| Some((pos, self.get(pos)?)))
+    }
+
+    /// Every diagonal running down and to the right, starting from the bottom-left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Vec2, &T)>> {
+        let starts = (0..self.height as isize)
+            .rev()
+            .map(|y| Vec2 { x: 0, y })
+            .chain((1..self.width as isize).map(|x| Vec2 { x, y: 0 }));
+        starts.map(|start| self.ray(start, Vec2 { x: 1, y: 1 }))
+    }
+
+    /// Every diagonal running up and to the right, starting from the top-left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Vec2, &T)>> {
+        let bottom = self.height as isize - 1;
+        let starts = (0..self.height as isize)
+            .map(|y| Vec2 { x: 0, y })
+            .chain((1..self.width as isize).map(move |x| Vec2 { x, y: bottom }));
+        starts.map(|start| self.ray(start, Vec2 { x: 1, y: -1 }))
+    }
+
+    /// The first position, row by row, whose cell matches.
+    pub fn find(&self, f: impl FnMut(&T) -> bool) -> Option<Vec2> {
+        self.cells.iter().position(f).map(|i| self.position(i))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &Self::Output {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!(
+                "{pos:?} is outside of a {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is outside of a {width}x{height} grid"),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef\n", |_, ch| Some(ch)).unwrap()
+    }
+
+    fn v(x: isize, y: isize) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(v(2, 1)), Some(&'f'));
+        assert_eq!(grid.get(v(3, 1)), None);
+        assert_eq!(grid.get(v(-1, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let error = Grid::parse("ab\nabc", |_, ch| Some(ch)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let error = Grid::parse("..\n.#", |_, ch| (ch == '.').then_some(ch)).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "#"));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbors(v(0, 0)).collect::<Vec<_>>(),
+            [v(1, 0), v(0, 1)]
+        );
+        assert_eq!(grid.neighbors(v(1, 1)).count(), 3);
+        assert_eq!(grid.neighbors8(v(1, 1)).count(), 5);
+        assert_eq!(grid.neighbors8(v(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = grid();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.row(2), None);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.column(3).count(), 0);
+
+        let cells = |lines: Vec<Vec<(Vec2, &char)>>| {
+            lines
+                .into_iter()
+                .map(|line| line.into_iter().map(|(_, c)| c).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        let diagonals = grid.diagonals().map(Iterator::collect).collect();
+        assert_eq!(cells(diagonals), ["d", "ae", "bf", "c"]);
+        let anti = grid.anti_diagonals().map(Iterator::collect).collect();
+        assert_eq!(cells(anti), ["a", "db", "ec", "f"]);
+    }
+
+    #[test]
+    fn test_find_and_update() {
+        let mut grid = grid();
+        assert_eq!(grid.find(|&c| c == 'e'), Some(v(1, 1)));
+        assert_eq!(grid.find(|&c| c == 'z'), None);
+
+        grid[v(1, 1)] = 'E';
+        *grid.get_mut(v(0, 0)).unwrap() = 'A';
+        assert_eq!(grid.to_string(), "Abc\ndEf\n");
+        assert_eq!(
+            grid.map(|c| c.is_uppercase())
+                .iter()
+                .filter(|(_, &u)| u)
+                .count(),
+            2
+        );
+    }
+}
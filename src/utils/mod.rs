@@ -0,0 +1,480 @@
+mod grid;
+pub mod parallel;
+pub mod rng;
+pub mod search;
+
+pub use grid::Grid;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub fn new(x: isize, y: isize) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    /// The four orthogonal neighbours, clockwise from the one above.
+    pub fn neighbors(&self) -> [Vec2; 4] {
+        [self.up(), self.right(), self.down(), self.left()]
+    }
+
+    /// The eight neighbours including diagonals, clockwise from the one above.
+    pub fn neighbors8(&self) -> [Vec2; 8] {
+        [
+            self.up(),
+            self.up().right(),
+            self.right(),
+            self.down().right(),
+            self.down(),
+            self.down().left(),
+            self.left(),
+            self.up().left(),
+        ]
+    }
+
+    pub fn manhattan_distance(&self, other: &Vec2) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance when diagonal steps are allowed, like a king moving on a chessboard.
+    pub fn chebyshev_distance(&self, other: &Vec2) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Turned a quarter anticlockwise as seen on screen, where y grows downwards: up becomes left.
+    pub fn rotate_left(&self) -> Vec2 {
+        Vec2 {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /// Turned a quarter clockwise as seen on screen, where y grows downwards: up becomes right.
+    pub fn rotate_right(&self) -> Vec2 {
+        Vec2 {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    /// Mirrored left to right.
+    pub fn reflect_x(&self) -> Vec2 {
+        Vec2 {
+            x: -self.x,
+            y: self.y,
+        }
+    }
+
+    /// Mirrored top to bottom.
+    pub fn reflect_y(&self) -> Vec2 {
+        Vec2 {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+
+    /// The smallest whole step in the same direction, so that stepping by it from one point
+    /// visits every grid point on the line to another. Zero stays zero.
+    pub fn direction(&self) -> Vec2 {
+        match gcd(self.x.unsigned_abs(), self.y.unsigned_abs()) {
+            0 => *self,
+            d => Vec2 {
+                x: self.x / d as isize,
+                y: self.y / d as isize,
+            },
+        }
+    }
+
+    /// Wrapped into `0..size` on both axes, as if the space repeats in every direction.
+    pub fn wrap(&self, size: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.x.rem_euclid(size.x),
+            y: self.y.rem_euclid(size.y),
+        }
+    }
+
+    /// The coordinates as unsigned indices, or `None` if either of them is negative.
+    pub fn to_usize(&self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+
+    pub fn right(&self) -> Vec2 {
+        Vec2 {
+            x: self.x + 1,
+            y: self.y,
+        }
+    }
+
+    pub fn left(&self) -> Vec2 {
+        Vec2 {
+            x: self.x - 1,
+            y: self.y,
+        }
+    }
+
+    pub fn up(&self) -> Vec2 {
+        Vec2 {
+            x: self.x,
+            y: self.y - 1,
+        }
+    }
+
+    pub fn down(&self) -> Vec2 {
+        Vec2 {
+            x: self.x,
+            y: self.y + 1,
+        }
+    }
+}
+
+impl From<(isize, isize)> for Vec2 {
+    fn from(value: (isize, isize)) -> Self {
+        Vec2 {
+            x: value.0,
+            y: value.1,
+        }
+    }
+}
+
+impl std::ops::Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec2 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl std::ops::Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec2 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl std::ops::Mul<isize> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Vec2 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl std::ops::Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Self::Output {
+        Vec2 {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl From<Dir> for Vec2 {
+    fn from(dir: Dir) -> Self {
+        dir.delta()
+    }
+}
+
+impl TryFrom<Vec2> for Dir {
+    type Error = anyhow::Error;
+
+    /// The direction of a single orthogonal step.
+    fn try_from(value: Vec2) -> Result<Self, Self::Error> {
+        match (value.x, value.y) {
+            (0, -1) => Ok(Dir::North),
+            (0, 1) => Ok(Dir::South),
+            (1, 0) => Ok(Dir::East),
+            (-1, 0) => Ok(Dir::West),
+            _ => Err(anyhow::anyhow!(
+                "{value:?} isn't a step in one of the four directions"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Dir {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Dir {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
+    pub fn all() -> impl Iterator<Item = Dir> {
+        Dir::ALL.into_iter()
+    }
+
+    pub fn turn_left(&self) -> Dir {
+        match self {
+            Dir::North => Dir::West,
+            Dir::West => Dir::South,
+            Dir::South => Dir::East,
+            Dir::East => Dir::North,
+        }
+    }
+
+    pub fn turn_right(&self) -> Dir {
+        match self {
+            Dir::North => Dir::East,
+            Dir::East => Dir::South,
+            Dir::South => Dir::West,
+            Dir::West => Dir::North,
+        }
+    }
+
+    pub fn reverse(&self) -> Dir {
+        match self {
+            Dir::North => Dir::South,
+            Dir::South => Dir::North,
+            Dir::East => Dir::West,
+            Dir::West => Dir::East,
+        }
+    }
+
+    /// Reads `^`, `v`, `<` or `>`, where `^` is north.
+    pub fn from_arrow(ch: char) -> Option<Dir> {
+        match ch {
+            '^' => Some(Dir::North),
+            'v' => Some(Dir::South),
+            '>' => Some(Dir::East),
+            '<' => Some(Dir::West),
+            _ => None,
+        }
+    }
+
+    /// Reads `N`, `S`, `E` or `W`.
+    pub fn from_compass(ch: char) -> Option<Dir> {
+        match ch {
+            'N' => Some(Dir::North),
+            'S' => Some(Dir::South),
+            'E' => Some(Dir::East),
+            'W' => Some(Dir::West),
+            _ => None,
+        }
+    }
+
+    /// A single step in this direction, where north is up the screen.
+    pub fn delta(&self) -> Vec2 {
+        match self {
+            Dir::North => Vec2 { x: 0, y: -1 },
+            Dir::South => Vec2 { x: 0, y: 1 },
+            Dir::East => Vec2 { x: 1, y: 0 },
+            Dir::West => Vec2 { x: -1, y: 0 },
+        }
+    }
+
+    /// The smallest angle to turn through to face `other`, in degrees: 0, 90 or 180.
+    pub fn angle(&self, other: Dir) -> u32 {
+        if *self == other {
+            0
+        } else if self.reverse() == other {
+            180
+        } else {
+            90
+        }
+    }
+}
+
+impl TryFrom<char> for Dir {
+    type Error = anyhow::Error;
+
+    /// Reads an arrow or a compass letter.
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Dir::from_arrow(ch)
+            .or_else(|| Dir::from_compass(ch))
+            .ok_or_else(|| anyhow::anyhow!("{ch:?} isn't a direction"))
+    }
+}
+
+impl std::str::FromStr for Dir {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Dir::try_from(ch),
+            _ => Err(anyhow::anyhow!("{s:?} isn't a direction")),
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let v = Vec2::new(2, -3);
+        assert_eq!(v * 3, Vec2::new(6, -9));
+        assert_eq!(-v, Vec2::new(-2, 3));
+        assert_eq!(v + -v, Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_rotate_and_reflect() {
+        let up = Vec2::new(0, -1);
+        assert_eq!(up.rotate_left(), Vec2::new(-1, 0));
+        assert_eq!(up.rotate_right(), Vec2::new(1, 0));
+        assert_eq!(
+            Vec2::new(3, 1).rotate_right().rotate_left(),
+            Vec2::new(3, 1)
+        );
+        assert_eq!(
+            Vec2::new(3, 1).rotate_right().rotate_right(),
+            -Vec2::new(3, 1)
+        );
+        assert_eq!(Vec2::new(3, 1).reflect_x(), Vec2::new(-3, 1));
+        assert_eq!(Vec2::new(3, 1).reflect_y(), Vec2::new(3, -1));
+    }
+
+    #[test]
+    fn test_distances_and_neighbors() {
+        let a = Vec2::new(1, 1);
+        let b = Vec2::new(4, -1);
+        assert_eq!(a.manhattan_distance(&b), 5);
+        assert_eq!(a.chebyshev_distance(&b), 3);
+        assert!(a.neighbors8().iter().all(|n| a.chebyshev_distance(n) == 1));
+        let distinct = a.neighbors8().into_iter().collect::<HashSet<_>>();
+        assert_eq!(distinct.len(), 8);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Vec2::new(6, -4).direction(), Vec2::new(3, -2));
+        assert_eq!(Vec2::new(0, 5).direction(), Vec2::new(0, 1));
+        assert_eq!(Vec2::new(-7, 0).direction(), Vec2::new(-1, 0));
+        assert_eq!(Vec2::ZERO.direction(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_wrap_and_to_usize() {
+        let size = Vec2::new(11, 7);
+        assert_eq!(Vec2::new(-1, 15).wrap(size), Vec2::new(10, 1));
+        assert_eq!(Vec2::new(3, 4).wrap(size), Vec2::new(3, 4));
+        assert_eq!(Vec2::new(3, 4).to_usize(), Some((3, 4)));
+        assert_eq!(Vec2::new(3, -4).to_usize(), None);
+    }
+
+    #[test]
+    fn test_dir_conversion() {
+        for dir in Dir::all() {
+            assert_eq!(Dir::try_from(Vec2::from(dir)).unwrap(), dir);
+        }
+        assert!(Dir::try_from(Vec2::new(1, 1)).is_err());
+        assert!(Dir::try_from(Vec2::new(0, 2)).is_err());
+    }
+
+    #[test]
+    fn test_dir_turns() {
+        for dir in Dir::all() {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(dir.turn_right().delta(), dir.delta().rotate_right());
+            assert_eq!(dir.turn_left().delta(), dir.delta().rotate_left());
+            assert_eq!(dir.reverse().delta(), -dir.delta());
+            assert_eq!(dir.angle(dir), 0);
+            assert_eq!(dir.angle(dir.turn_left()), 90);
+            assert_eq!(dir.angle(dir.reverse()), 180);
+        }
+        assert_eq!(Dir::all().collect::<HashSet<_>>().len(), 4);
+    }
+
+    #[test]
+    fn test_dir_parse() {
+        assert_eq!(Dir::try_from('^').unwrap(), Dir::North);
+        assert_eq!(Dir::try_from('v').unwrap(), Dir::South);
+        assert_eq!("<".parse::<Dir>().unwrap(), Dir::West);
+        assert_eq!("E".parse::<Dir>().unwrap(), Dir::East);
+        assert!(Dir::try_from('x').is_err());
+        assert_eq!(Dir::from_arrow('N'), None);
+        assert_eq!(Dir::from_compass('^'), None);
+        assert!("NE".parse::<Dir>().is_err());
+    }
+
+    fn vec2() -> impl Strategy<Value = Vec2> {
+        (-1_000_000isize..1_000_000, -1_000_000isize..1_000_000).prop_map(Vec2::from)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_add_sub_round_trip(a in vec2(), b in vec2()) {
+            prop_assert_eq!(a + b - b, a);
+            prop_assert_eq!(a - b + b, a);
+            prop_assert_eq!(a + b, b + a);
+            prop_assert_eq!(a - a, Vec2::ZERO);
+            prop_assert_eq!(a + -a, Vec2::ZERO);
+        }
+
+        #[test]
+        fn prop_rotations(a in vec2()) {
+            prop_assert_eq!(a.rotate_left().rotate_right(), a);
+            prop_assert_eq!(a.rotate_right().rotate_right(), -a);
+            prop_assert_eq!(a.rotate_left().rotate_left().rotate_left(), a.rotate_right());
+            prop_assert_eq!(a.rotate_right().manhattan_distance(&Vec2::ZERO), a.manhattan_distance(&Vec2::ZERO));
+            prop_assert_eq!(a.reflect_x().reflect_x(), a);
+            prop_assert_eq!(a.reflect_y().reflect_y(), a);
+        }
+
+        #[test]
+        fn prop_distances(a in vec2(), b in vec2(), c in vec2()) {
+            prop_assert_eq!(a.manhattan_distance(&b), b.manhattan_distance(&a));
+            prop_assert_eq!((a + c).manhattan_distance(&(b + c)), a.manhattan_distance(&b));
+            prop_assert!(a.manhattan_distance(&c) <= a.manhattan_distance(&b) + b.manhattan_distance(&c));
+            prop_assert!(a.chebyshev_distance(&b) <= a.manhattan_distance(&b));
+            prop_assert!(a.manhattan_distance(&b) <= 2 * a.chebyshev_distance(&b));
+        }
+
+        #[test]
+        fn prop_direction(a in vec2(), n in 1isize..100) {
+            let dir = a.direction();
+            prop_assert_eq!((a * n).direction(), dir);
+            if a != Vec2::ZERO {
+                let steps = a.x.checked_div(dir.x).or(a.y.checked_div(dir.y)).unwrap();
+                prop_assert!(steps > 0);
+                prop_assert_eq!(dir * steps, a);
+            }
+        }
+
+        #[test]
+        fn prop_wrap(a in vec2(), w in 1isize..1000, h in 1isize..1000, k in vec2()) {
+            let size = Vec2::new(w, h);
+            let wrapped = a.wrap(size);
+            prop_assert!((0..w).contains(&wrapped.x) && (0..h).contains(&wrapped.y));
+            prop_assert_eq!(wrapped.wrap(size), wrapped);
+            let shifted = a + Vec2::new(size.x * (k.x % 100), size.y * (k.y % 100));
+            prop_assert_eq!(shifted.wrap(size), wrapped);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Loops over independent items that run on rayon's thread pool with the `parallel` feature, and
+//! one after another without it. Results come back in the same order either way, so the answers
+//! don't depend on the feature.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `f` applied to every item, in the order of `items`.
+#[cfg(feature = "parallel")]
+pub fn map<T, R>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    items.par_iter().map(f).collect()
+}
+
+/// `f` applied to every item, in the order of `items`.
+#[cfg(not(feature = "parallel"))]
+pub fn map<T, R>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    items.iter().map(f).collect()
+}
+
+/// Folds the items into accumulators started by `init`, then merges the accumulators with
+/// `merge`. Items are split between threads in no particular order, so `fold` and `merge` have
+/// to give the same result whatever the order, like summing counts does.
+#[cfg(feature = "parallel")]
+pub fn fold<T, A>(
+    items: &[T],
+    init: impl Fn() -> A + Sync + Send,
+    fold: impl Fn(A, &T) -> A + Sync + Send,
+    merge: impl Fn(A, A) -> A + Sync + Send,
+) -> A
+where
+    T: Sync,
+    A: Send,
+{
+    items.par_iter().fold(&init, fold).reduce(&init, merge)
+}
+
+/// Folds the items into accumulators started by `init`, then merges the accumulators with
+/// `merge`. Items are split between threads in no particular order, so `fold` and `merge` have
+/// to give the same result whatever the order, like summing counts does.
+#[cfg(not(feature = "parallel"))]
+pub fn fold<T, A>(
+    items: &[T],
+    init: impl Fn() -> A + Sync + Send,
+    fold: impl Fn(A, &T) -> A + Sync + Send,
+    _merge: impl Fn(A, A) -> A + Sync + Send,
+) -> A
+where
+    T: Sync,
+    A: Send,
+{
+    items.iter().fold(init(), fold)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items = (0..1000).collect::<Vec<u32>>();
+        assert_eq!(
+            map(&items, |n| n * 2),
+            (0..2000).step_by(2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_fold() {
+        let items = (0..1000).map(|n| n % 7).collect::<Vec<u32>>();
+        let counts = fold(
+            &items,
+            HashMap::new,
+            |mut counts: HashMap<u32, usize>, &n| {
+                *counts.entry(n).or_default() += 1;
+                counts
+            },
+            |mut a, b| {
+                for (n, count) in b {
+                    *a.entry(n).or_default() += count;
+                }
+                a
+            },
+        );
+        assert_eq!(counts.len(), 7);
+        assert_eq!(counts.values().sum::<usize>(), 1000);
+        assert_eq!(counts[&0], 143);
+    }
+}
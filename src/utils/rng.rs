@@ -0,0 +1,143 @@
+//! A small seeded random number generator for making up puzzle inputs. It's SplitMix64, which is
+//! plenty random for test data and gives the same numbers for the same seed on every platform.
+
+use std::ops::Range;
+
+use super::{Dir, Grid, Vec2};
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Can't pick a number below zero");
+        // Multiplying instead of taking the remainder keeps the bias negligible for small `n`.
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(!range.is_empty(), "Can't pick a number from {range:?}");
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add(self.below(span) as i64)
+    }
+
+    /// An index into something `len` long.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// The side of a square grid with `size` times as many cells as one `base` cells across, so that
+/// generated grids grow by area rather than by side.
+pub fn scaled_side(base: usize, size: usize) -> usize {
+    (base as f64 * (size as f64).sqrt()).round() as usize
+}
+
+/// A maze of `cells` by `cells` rooms with exactly one way between any two of them, as a grid of
+/// walls `2 * cells + 1` across. Rooms are at odd positions, and the walls between them have one
+/// odd coordinate.
+pub fn maze(rng: &mut Rng, cells: usize) -> Grid<bool> {
+    let side = 2 * cells.max(1) + 1;
+    let mut walls = Grid::filled(side, side, true);
+    let start = Vec2::new(1, 1);
+    walls[start] = false;
+    let mut stack = vec![start];
+    while let Some(&room) = stack.last() {
+        let unvisited = Dir::all()
+            .filter(|&dir| walls.get(room + dir.delta() * 2) == Some(&true))
+            .collect::<Vec<_>>();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let dir = *rng.choose(&unvisited);
+        walls[room + dir.delta()] = false;
+        walls[room + dir.delta() * 2] = false;
+        stack.push(room + dir.delta() * 2);
+    }
+    walls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        // The first output of SplitMix64 seeded with 0.
+        assert_eq!(numbers(0)[0], 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(1);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let n = rng.range(-3..3);
+            assert!((-3..3).contains(&n));
+            seen[(n + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.range(i64::MIN..i64::MIN + 1), i64::MIN);
+
+        let mut items = (0..50).collect::<Vec<_>>();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+        assert_eq!(scaled_side(100, 4), 200);
+    }
+
+    #[test]
+    fn test_maze() {
+        let walls = maze(&mut Rng::new(3), 10);
+        assert_eq!((walls.width(), walls.height()), (21, 21));
+        let open = walls.iter().filter(|(_, &wall)| !wall).count();
+        // A tree of 100 rooms has 99 passages between them.
+        assert_eq!(open, 100 + 99);
+        let reached = crate::utils::search::bfs(Vec2::new(1, 1), |pos| {
+            walls
+                .neighbors(*pos)
+                .filter(|next| !walls[*next])
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(reached.len(), open);
+    }
+}
@@ -0,0 +1,291 @@
+//! Graph searches over a neighbour function, so the days only have to describe their graph.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// The number of steps from `start` to every node it can reach.
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> FxHashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut steps = FxHashMap::default();
+    steps.insert(start.clone(), 0);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, n)) = queue.pop_front() {
+        for next in neighbors(&node) {
+            if let Entry::Vacant(entry) = steps.entry(next.clone()) {
+                entry.insert(n + 1);
+                queue.push_back((next, n + 1));
+            }
+        }
+    }
+    steps
+}
+
+/// The cheapest way from `start` to any node that satisfies `goal`, as that node and the cost of
+/// getting there. `neighbors` returns each next node along with the cost of stepping to it.
+pub fn dijkstra<N, I>(
+    start: N,
+    neighbors: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(N, usize)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(start, neighbors, |_| 0, goal)
+}
+
+/// [`dijkstra`], guided towards the goal by `heuristic`. The heuristic must never overestimate the
+/// remaining cost, or the path found might not be the cheapest.
+pub fn astar<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(N, usize)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut costs = FxHashMap::default();
+    costs.insert(start.clone(), 0);
+    let mut heap = BinaryHeap::from([State {
+        priority: heuristic(&start),
+        cost: 0,
+        node: start,
+    }]);
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if goal(&node) {
+            return Some((node, cost));
+        }
+        // A cheaper way to this node was already found after this one was queued.
+        if costs.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|&best| next_cost < best) {
+                costs.insert(next.clone(), next_cost);
+                heap.push(State {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Every node reachable from a start node, with its lowest cost and every node that leads to it
+/// at that cost.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N> {
+    costs: FxHashMap<N, usize>,
+    predecessors: FxHashMap<N, Vec<N>>,
+}
+
+/// Runs [`dijkstra`] over the whole graph, keeping every predecessor on a shortest path instead
+/// of just one.
+pub fn all_shortest_paths<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> ShortestPaths<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    let mut costs = FxHashMap::default();
+    let mut predecessors: FxHashMap<N, Vec<N>> = FxHashMap::default();
+    costs.insert(start.clone(), 0);
+    let mut heap = BinaryHeap::from([State {
+        priority: 0,
+        cost: 0,
+        node: start,
+    }]);
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if costs.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(&best) if next_cost > best => {}
+                Some(&best) if next_cost == best => {
+                    predecessors.entry(next).or_default().push(node.clone());
+                }
+                _ => {
+                    costs.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), vec![node.clone()]);
+                    heap.push(State {
+                        priority: next_cost,
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+
+    ShortestPaths {
+        costs,
+        predecessors,
+    }
+}
+
+impl<N: Clone + Eq + Hash> ShortestPaths<N> {
+    pub fn cost(&self, node: &N) -> Option<usize> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn predecessors(&self, node: &N) -> &[N] {
+        self.predecessors.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every node on any shortest path from the start to one of `ends`, including both ends.
+    pub fn nodes_on_paths(&self, ends: impl IntoIterator<Item = N>) -> FxHashSet<N> {
+        let mut seen = FxHashSet::default();
+        let mut stack = ends
+            .into_iter()
+            .filter(|end| self.costs.contains_key(end))
+            .collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            if seen.insert(node.clone()) {
+                stack.extend(self.predecessors(&node).iter().cloned());
+            }
+        }
+        seen
+    }
+
+    /// Every shortest path from the start to `end`, each one starting at the start.
+    pub fn paths(&self, end: &N) -> Vec<Vec<N>> {
+        if !self.costs.contains_key(end) {
+            return Vec::new();
+        }
+        let predecessors = self.predecessors(end);
+        if predecessors.is_empty() {
+            return vec![vec![end.clone()]];
+        }
+        predecessors
+            .iter()
+            .flat_map(|previous| self.paths(previous))
+            .map(|mut path| {
+                path.push(end.clone());
+                path
+            })
+            .collect()
+    }
+}
+
+/// A queued node. The heap is a max-heap, so the ordering is reversed to pop the lowest priority
+/// first.
+struct State<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> PartialEq for State<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for State<N> {}
+
+impl<N> PartialOrd for State<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for State<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{Grid, Vec2};
+
+    use super::*;
+
+    fn maze() -> Grid<bool> {
+        let maze = "\
+            ..#.\n\
+            .##.\n\
+            ....\n";
+        Grid::parse(maze, |_, ch| Some(ch == '#')).unwrap()
+    }
+
+    fn open(maze: &Grid<bool>, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        maze.neighbors(pos).filter(|&next| !maze[next])
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let steps = bfs(Vec2 { x: 0, y: 0 }, |&pos| open(&maze, pos));
+        assert_eq!(steps[&Vec2 { x: 3, y: 0 }], 7);
+        assert_eq!(steps[&Vec2 { x: 1, y: 0 }], 1);
+        assert!(!steps.contains_key(&Vec2 { x: 2, y: 0 }));
+    }
+
+    /// A square where going round the top costs 1 + 5 and going round the bottom costs 2 + 2.
+    fn square(node: &char) -> Vec<(char, usize)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 2)],
+            'b' => vec![('d', 5)],
+            'c' => vec![('d', 2)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        assert_eq!(dijkstra('a', square, |&n| n == 'd'), Some(('d', 4)));
+        assert_eq!(dijkstra('a', square, |&n| n == 'z'), None);
+        assert_eq!(astar('a', square, |_| 1, |&n| n == 'd'), Some(('d', 4)));
+
+        let maze = maze();
+        let end = Vec2 { x: 3, y: 0 };
+        let result = astar(
+            Vec2 { x: 0, y: 0 },
+            |&pos| open(&maze, pos).map(|next| (next, 1)),
+            |pos| pos.manhattan_distance(&end),
+            |&pos| pos == end,
+        );
+        assert_eq!(result, Some((end, 7)));
+    }
+
+    #[test]
+    fn test_all_shortest_paths() {
+        let diamond = |node: &char| match node {
+            'a' => vec![('b', 1), ('c', 1)],
+            'b' | 'c' => vec![('d', 1)],
+            'd' => vec![('e', 1)],
+            _ => vec![],
+        };
+        let shortest = all_shortest_paths('a', diamond);
+        assert_eq!(shortest.cost(&'e'), Some(3));
+        assert_eq!(shortest.predecessors(&'d').len(), 2);
+        assert_eq!(
+            shortest.nodes_on_paths(['d']),
+            FxHashSet::from_iter(['a', 'b', 'c', 'd'])
+        );
+        let mut paths = shortest.paths(&'e');
+        paths.sort();
+        assert_eq!(paths, [vec!['a', 'b', 'd', 'e'], vec!['a', 'c', 'd', 'e']]);
+
+        let shortest = all_shortest_paths('a', square);
+        assert_eq!(shortest.paths(&'d'), [vec!['a', 'c', 'd']]);
+        assert_eq!(shortest.paths(&'z'), Vec::<Vec<char>>::new());
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::{
+    input::{FsProvider, InputKind, InputNotFound, InputProvider},
+    solution::{Answer, Params, Puzzle},
+    utils::parallel,
+};
+
+/// The known answers for a single day, stored in `answers/NN.toml`:
+///
+/// ```toml
+/// part1 = 11
+/// part2 = "co,de,ka,ta"
+///
+/// [example]
+/// part1 = 7
+///
+/// [examples.e2]
+/// part2 = 3
+/// params = { size = 6 }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Answers {
+    #[serde(flatten)]
+    pub real: Expected,
+    /// The answers for `NNe.txt`.
+    #[serde(default)]
+    pub example: Expected,
+    /// The answers for every other example, by name.
+    #[serde(default)]
+    pub examples: BTreeMap<String, Expected>,
+}
+
+/// Where the answer files are kept: an `answers/` directory beside each of the directories that
+/// inputs are searched for in, tried in the same order, so they're found from any working
+/// directory too.
+#[derive(Debug, Clone)]
+pub struct AnswerDirs {
+    pub dirs: Vec<PathBuf>,
+}
+
+impl AnswerDirs {
+    /// The answer directories that go with [`FsProvider::resolve`].
+    pub fn resolve(input_dir: Option<&Path>) -> AnswerDirs {
+        let inputs = FsProvider::resolve(input_dir);
+        AnswerDirs {
+            dirs: inputs
+                .dirs
+                .iter()
+                .map(|dir| dir.parent().unwrap_or(dir).join("answers"))
+                .collect(),
+        }
+    }
+
+    /// Loads the answers for a day from the first directory that has them, or `None` if none of
+    /// them do.
+    pub fn load(&self, day: u16) -> anyhow::Result<Option<Answers>> {
+        let Some(path) = self.path(day) else {
+            return Ok(None);
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Can't read {}", path.display()))?;
+        toml::from_str(&text)
+            .map(Some)
+            .with_context(|| format!("Invalid answers file {}", path.display()))
+    }
+
+    fn path(&self, day: u16) -> Option<PathBuf> {
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(format!("{day:02}.toml")))
+            .find(|path| path.is_file())
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Expected {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    /// Parameters the input needs, such as the smaller grid of an example.
+    #[serde(default)]
+    pub params: BTreeMap<String, toml::Value>,
+}
+
+impl Answers {
+    /// The answers expected for an input, if any are known.
+    pub fn expected(&self, kind: &InputKind) -> Option<&Expected> {
+        match kind {
+            InputKind::Real => Some(&self.real),
+            InputKind::Example(name) => self
+                .examples
+                .get(name)
+                .or((name == InputKind::DEFAULT_EXAMPLE).then_some(&self.example)),
+        }
+    }
+}
+
+impl Expected {
+    pub fn params(&self) -> Params {
+        self.params
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+}
+
+/// Every example of a day that either has an input or has answers.
+pub fn examples(day: u16, answers: &Answers, inputs: &dyn InputProvider) -> Vec<InputKind> {
+    let mut names = inputs.examples(day).into_iter().collect::<BTreeSet<_>>();
+    names.extend(answers.examples.keys().cloned());
+    if answers.example.part1.is_some() || answers.example.part2.is_some() {
+        names.insert(InputKind::DEFAULT_EXAMPLE.to_string());
+    }
+    names.into_iter().map(InputKind::Example).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    Missing,
+    Error(String),
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected}, got {actual})")
+            }
+            Status::Missing => write!(f, "missing"),
+            Status::Error(e) => write!(f, "ERROR ({e})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u16,
+    pub input: InputKind,
+    pub part1: Status,
+    pub part2: Status,
+}
+
+impl Check {
+    pub fn is_ok(&self) -> bool {
+        [&self.part1, &self.part2]
+            .iter()
+            .all(|s| matches!(s, Status::Pass | Status::Missing))
+    }
+}
+
+/// Runs every puzzle against its real input and all of its examples, and compares the results to
+/// the stored answers. Parts without a stored answer aren't run at all, but a day without an
+/// answers file is an error.
+pub fn verify(
+    puzzles: &[&dyn Puzzle],
+    inputs: &dyn InputProvider,
+    answer_dirs: &AnswerDirs,
+) -> anyhow::Result<Vec<Check>> {
+    let checks = parallel::map(puzzles, |&puzzle| {
+        let Some(answers) = answer_dirs.load(puzzle.day())? else {
+            let tried = answer_dirs.dirs.iter().map(|dir| dir.display().to_string());
+            let error = Status::Error(format!(
+                "no {:02}.toml in any of {}",
+                puzzle.day(),
+                itertools::join(tried, ", ")
+            ));
+            return Ok(vec![Check {
+                day: puzzle.day(),
+                input: InputKind::Real,
+                part1: error.clone(),
+                part2: error,
+            }]);
+        };
+        let kinds =
+            std::iter::once(InputKind::Real).chain(examples(puzzle.day(), &answers, inputs));
+        Ok(kinds
+            .map(|kind| {
+                let expected = answers.expected(&kind).cloned().unwrap_or_default();
+                check(puzzle, inputs, kind, &expected)
+            })
+            .collect::<Vec<_>>())
+    });
+    checks
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map(|checks| checks.concat())
+}
+
+/// Checks one puzzle against one of its inputs.
+pub fn check(
+    puzzle: &dyn Puzzle,
+    inputs: &dyn InputProvider,
+    kind: InputKind,
+    expected: &Expected,
+) -> Check {
+    let day = puzzle.day();
+    let all = |status: Status| Check {
+        day,
+        input: kind.clone(),
+        part1: status.clone(),
+        part2: status,
+    };
+
+    if expected.part1.is_none() && expected.part2.is_none() {
+        return all(Status::Missing);
+    }
+
+    let data = match inputs.input(day, &kind) {
+        Ok(data) => data,
+        Err(e) if e.is::<InputNotFound>() => return all(Status::Missing),
+        Err(e) => return all(Status::Error(e.to_string())),
+    };
+
+    let parsed = match puzzle.parse(&data, &expected.params()) {
+        Ok(parsed) => parsed,
+        Err(e) => return all(Status::Error(e.to_string())),
+    };
+
+    let compare =
+        |expected: &Option<Answer>, solve: &dyn Fn() -> anyhow::Result<Answer>| match expected {
+            None => Status::Missing,
+            Some(expected) => match solve() {
+                Ok(actual) if actual == *expected => Status::Pass,
+                Ok(actual) => Status::Fail {
+                    expected: expected.clone(),
+                    actual,
+                },
+                Err(e) => Status::Error(e.to_string()),
+            },
+        };
+
+    let part1 = compare(&expected.part1, &|| parsed.part1());
+    let part2 = compare(&expected.part2, &|| parsed.part2());
+    Check {
+        day,
+        input: kind,
+        part1,
+        part2,
+    }
+}
+
+pub fn print_table(checks: &[Check]) {
+    println!(
+        "{:>3}  {:<12}  {:<10}  {:<10}",
+        "Day", "Input", "Part 1", "Part 2"
+    );
+    for check in checks {
+        println!(
+            "{:>3}  {:<12}  {:<10}  {:<10}",
+            check.day,
+            check.input.to_string(),
+            check.part1.to_string(),
+            check.part2.to_string()
+        );
+    }
+
+    let statuses = checks.iter().flat_map(|c| [&c.part1, &c.part2]);
+    let (mut pass, mut fail, mut missing) = (0, 0, 0);
+    for status in statuses {
+        match status {
+            Status::Pass => pass += 1,
+            Status::Missing => missing += 1,
+            Status::Fail { .. } | Status::Error(_) => fail += 1,
+        }
+    }
+    println!();
+    println!("{pass} passed, {fail} failed, {missing} missing");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers: Answers = toml::from_str(
+            r#"
+            part1 = 380
+            part2 = "26,50"
+
+            [example]
+            part1 = 22
+
+            [examples.e2]
+            part2 = 4
+            params = { size = 6 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(answers.real.part1, Some(Answer::Num(380)));
+        assert_eq!(answers.real.part2, Some(Answer::Text("26,50".to_string())));
+        assert_eq!(answers.example.part1, Some(Answer::Num(22)));
+        assert_eq!(answers.example.part2, None);
+
+        let e2 = answers.expected(&InputKind::Example("e2".into())).unwrap();
+        assert_eq!(e2.part2, Some(Answer::Num(4)));
+        assert_eq!(e2.params["size"].as_integer(), Some(6));
+        assert!(answers.expected(&InputKind::example()).is_some());
+        assert!(answers.expected(&InputKind::Example("e3".into())).is_none());
+    }
+
+    #[test]
+    fn test_answer_dirs() {
+        let dirs = AnswerDirs::resolve(Some(Path::new("/puzzles/inputs")));
+        assert!(dirs.dirs.contains(&PathBuf::from("/puzzles/answers")));
+        assert_eq!(
+            dirs.dirs.last(),
+            Some(&Path::new(env!("CARGO_MANIFEST_DIR")).join("answers"))
+        );
+
+        let root = tempfile::tempdir().unwrap();
+        let dirs = AnswerDirs {
+            dirs: vec![root.path().join("missing"), root.path().to_path_buf()],
+        };
+        assert!(dirs.load(1).unwrap().is_none());
+        std::fs::write(root.path().join("01.toml"), "part1 = 5\n").unwrap();
+        let answers = dirs.load(1).unwrap().unwrap();
+        assert_eq!(answers.real.part1, Some(Answer::Num(5)));
+        std::fs::write(root.path().join("02.toml"), "part1 = \n").unwrap();
+        assert!(dirs.load(2).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use aoc2024::{
+    input::FsProvider,
+    registry,
+    verify::{self, AnswerDirs},
+};
+
+#[test]
+fn test_examples() {
+    let inputs = FsProvider::default();
+    let answer_dirs = AnswerDirs::resolve(None);
+    for puzzle in registry() {
+        let answers = answer_dirs.load(puzzle.day()).unwrap().unwrap();
+        for kind in verify::examples(puzzle.day(), &answers, &inputs) {
+            let expected = answers.expected(&kind).cloned().unwrap_or_default();
+            let check = verify::check(puzzle, &inputs, kind, &expected);
+            assert!(check.is_ok(), "{check:?}");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use aoc2024::{params::Params, registry};
+
+#[test]
+fn test_generated_inputs_parse() {
+    for puzzle in registry() {
+        let day = puzzle.day();
+        let input = puzzle.generate(1, 1).unwrap();
+        assert_eq!(
+            input,
+            puzzle.generate(1, 1).unwrap(),
+            "day {day} isn't repeatable"
+        );
+        assert_ne!(
+            input,
+            puzzle.generate(2, 1).unwrap(),
+            "day {day} ignores the seed"
+        );
+        if let Err(e) = puzzle.parse(&input, &Params::new()) {
+            panic!("day {day} generated an input it can't parse: {e}");
+        }
+    }
+}
+
+#[test]
+fn test_generated_inputs_scale() {
+    for puzzle in registry() {
+        // Day 17's programs are always the same length.
+        if puzzle.day() == 17 {
+            continue;
+        }
+        let small = puzzle.generate(1, 1).unwrap().len();
+        let big = puzzle.generate(1, 4).unwrap().len();
+        assert!(big > small, "day {} doesn't grow", puzzle.day());
+    }
+}
@@ -0,0 +1,47 @@
+//! Throws variations on every example at the day, solving both parts of any the parser accepts, a
+//! quick stand-in for the fuzz targets in `fuzz/` that runs on stable.
+
+use aoc2024::{
+    fuzz,
+    input::{FsProvider, InputKind, InputProvider},
+    registry,
+};
+
+/// Variations on a valid input that tend to trip up parsers: cut short, missing or repeated
+/// lines, single characters swapped for others, and numbers too big for any integer type.
+fn mutations(input: &str) -> impl Iterator<Item = String> + '_ {
+    let lines = input.lines().collect::<Vec<_>>();
+    let truncated = input.char_indices().map(|(i, _)| input[..i].to_string());
+    let without_line = (0..lines.len()).map({
+        let lines = lines.clone();
+        move |i| [&lines[..i], &lines[i + 1..]].concat().join("\n")
+    });
+    let repeated_line = (0..lines.len()).map(move |i| {
+        let mut lines = lines.clone();
+        lines.insert(i, lines[i]);
+        lines.join("\n")
+    });
+    let replaced = input.char_indices().flat_map(move |(i, ch)| {
+        ["", "0", "-", "\n", " ", "#", ".", "99999999999999999999"]
+            .into_iter()
+            .map(move |with| format!("{}{with}{}", &input[..i], &input[i + ch.len_utf8()..]))
+    });
+    truncated
+        .chain(without_line)
+        .chain(repeated_line)
+        .chain(replaced)
+}
+
+#[test]
+fn test_days_survive_mutated_examples() {
+    let inputs = FsProvider::default();
+    for puzzle in registry() {
+        let day = puzzle.day();
+        for name in inputs.examples(day) {
+            let input = inputs.input(day, &InputKind::Example(name)).unwrap();
+            for mutated in mutations(&input) {
+                fuzz::run(day, &mutated);
+            }
+        }
+    }
+}